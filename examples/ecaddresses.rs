@@ -17,7 +17,7 @@ async fn main() {
     let client = Factom::open_node();
     let heights = factomd::heights(&client).await.expect("Fetching Heights");
     let e_height = heights.result.entryblockheight;
-    let e_block = block::ecblock_by_height(&client, e_height as u32)
+    let e_block = block::ecblock_by_height(&client, Height(e_height as u32))
         .await
         .expect("Fetching Entry Credit Block by Height");
     let entries = e_block.result.ecblock.body.entries;
@@ -2,7 +2,8 @@
 use super::*;
 
 /// Retrieve the public and private parts of a Factoid or Entry Credit address
-///stored in the wallet.
+///stored in the wallet. If the wallet is encrypted it must be unlocked first,
+/// otherwise the secret can't be read.
 ///
 ///# Example
 ///```
@@ -27,6 +28,8 @@ pub async fn address(api: &Factom, address: &str) -> Result<ApiResponse<Address>
 }
 
 ///Retrieve all of the Factoid and Entry Credit addresses stored in the wallet.
+/// As with `address` the secrets are included, so an encrypted wallet must be
+/// unlocked first.
 ///
 ///# Example
 ///```
@@ -48,14 +51,16 @@ pub async fn address(api: &Factom, address: &str) -> Result<ApiResponse<Address>
 ///           .any(|address| address.public == my_address));
 /// }
 /// ```
+///
+/// An empty wallet returns an empty list of addresses rather than an error.
 pub async fn all_addresses(api: &Factom) -> Result<ApiResponse<AllAddresses>> {
     let req = ApiRequest::new("all-addresses");
     let response = requests::walletd_call(api, req).await;
-    parse(response).await
+    Ok(parse(response).await?.or_empty_wallet())
 }
 
-/// Be careful using this function! Ensure that you have backups of important keys
-/// before removing them. Given a factoid or entry-credit address, this command
+/// **Be careful using this function! Ensure that you have backups of important keys
+/// before removing them.** Given a factoid or entry-credit address, this command
 /// deletes the corresponding key pair from the wallet. Once executed, the user will
 /// no longer be able to retrieve the private key or make transactions with the
 /// address from this wallet. If the wallet is encrypted, it must be unlocked prior
@@ -84,6 +89,109 @@ pub async fn remove_address(api: &Factom, address: &str) -> Result<ApiResponse<R
     parse(response).await
 }
 
+/// Converts a human readable factoid address into the hex encoded RCD hash used
+/// in the `address` field of transaction inputs and outputs. The checksum is
+/// verified, returning a FetchError::InvalidAddress if it doesn't match.
+///
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// let rcd = address::user_to_rcd("FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q").unwrap();
+/// assert_eq!(rcd, "646f3e8750c550e4582eca5047546ffef89c13a175985e320232bacac81cc428");
+/// ```
+pub fn user_to_rcd(useraddress: &str) -> Result<String> {
+    let rcd_hash = decode_checked(useraddress, &FCT_PUB_PREFIX)?;
+    Ok(hex::encode(rcd_hash))
+}
+
+/// Converts a hex encoded RCD hash into its human readable factoid address.
+///
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// let rcd = "646f3e8750c550e4582eca5047546ffef89c13a175985e320232bacac81cc428";
+/// let address = address::rcd_to_user(rcd).unwrap();
+/// assert_eq!(address, "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q");
+/// ```
+pub fn rcd_to_user(rcd_hex: &str) -> Result<String> {
+    let rcd_hash = hex::decode(rcd_hex).map_err(|_| FetchError::InvalidAddress(rcd_hex.into()))?;
+    if rcd_hash.len() != 32 {
+        return Err(FetchError::InvalidAddress(rcd_hex.to_string()).into());
+    }
+    Ok(encode_checked(&FCT_PUB_PREFIX, &rcd_hash))
+}
+
+/// Checks an address offline, returning its type if the prefix is known and the
+/// checksum matches, otherwise a FetchError::InvalidAddress. Useful to catch
+/// typos before making any api calls.
+///
+/// # Example
+/// ```
+/// use factom::*;
+/// use factom::address::AddressType;
+///
+/// let address = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+/// assert_eq!(address::validate_address(address).unwrap(), AddressType::EntryCredit);
+/// ```
+pub fn validate_address(address: &str) -> Result<AddressType> {
+    let types: [(&[u8], AddressType); 6] = [
+        (&FCT_PUB_PREFIX, AddressType::Factoid),
+        (&FCT_SEC_PREFIX, AddressType::FactoidSecret),
+        (&EC_PUB_PREFIX, AddressType::EntryCredit),
+        (&EC_SEC_PREFIX, AddressType::EcSecret),
+        (&ID_PUB_PREFIX, AddressType::IdentityPublic),
+        (&ID_SEC_PREFIX, AddressType::IdentitySecret),
+    ];
+    types
+        .iter()
+        .find(|(prefix, _)| decode_checked(address, prefix).is_ok())
+        .map(|(_, address_type)| *address_type)
+        .ok_or_else(|| FetchError::InvalidAddress(address.to_string()).into())
+}
+
+/// Kinds of human readable address and key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressType {
+    /// FA public factoid address
+    Factoid,
+    /// EC public entry credit address
+    EntryCredit,
+    /// Fs secret factoid address
+    FactoidSecret,
+    /// Es secret entry credit address
+    EcSecret,
+    /// idpub identity public key
+    IdentityPublic,
+    /// idsec identity secret key
+    IdentitySecret,
+}
+
+/// Base58 encodes a prefix and key with a double sha256 checksum appended
+pub(crate) fn encode_checked(prefix: &[u8], key: &[u8]) -> String {
+    let mut data = Vec::with_capacity(prefix.len() + key.len() + CHECKSUM_LENGTH);
+    data.extend_from_slice(prefix);
+    data.extend_from_slice(key);
+    let checksum = utils::double_sha(&data);
+    data.extend_from_slice(&checksum[..CHECKSUM_LENGTH]);
+    bs58::encode(data).into_string()
+}
+
+/// Base58 decodes an address, verifying the prefix and checksum, returns the key
+pub(crate) fn decode_checked(address: &str, prefix: &[u8]) -> Result<Vec<u8>> {
+    let invalid = || FetchError::InvalidAddress(address.to_string());
+    let data = bs58::decode(address).into_vec().map_err(|_| invalid())?;
+    if data.len() != prefix.len() + 32 + CHECKSUM_LENGTH || !data.starts_with(prefix) {
+        return Err(invalid().into());
+    }
+    let (body, checksum) = data.split_at(data.len() - CHECKSUM_LENGTH);
+    if utils::double_sha(body)[..CHECKSUM_LENGTH] != *checksum {
+        return Err(invalid().into());
+    }
+    Ok(body[prefix.len()..].to_vec())
+}
+
 /// address function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Address {
@@ -94,6 +202,7 @@ pub struct Address {
 /// all-addresses function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AllAddresses {
+    #[serde(default, deserialize_with = "utils::null_as_default")]
     pub addresses: Vec<Address>,
 }
 
@@ -102,3 +211,120 @@ pub struct AllAddresses {
 pub struct RemoveAddress {
     pub success: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_wallet_addresses() {
+        let null = mock::result(json!({ "addresses": null }));
+        let error = mock::error(-32603, "Internal error: no addresses");
+        let server = mock::serve(vec![&null, &error]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        for _ in 0..2 {
+            let response = fetch(all_addresses(&client)).unwrap();
+            assert!(response.success());
+            assert!(response.result.addresses.is_empty());
+        }
+        server.requests();
+    }
+
+    #[test]
+    fn address_lookups_use_walletd() {
+        let fct = json!({
+            "public": "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q",
+            "secret": "Fs3E9gV6DXsYzf7Fqx1fVBQPQXV695eP3k5XbmHEZVRLkMdD9qCK"
+        });
+        let single = mock::result(fct.clone());
+        let all = mock::result(json!({ "addresses": [fct] }));
+        let server = mock::serve(vec![&single, &all]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let public = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+        let response = fetch(address(&client, public)).unwrap();
+        assert_eq!(response.result.public, public);
+        let response = fetch(all_addresses(&client)).unwrap();
+        assert_eq!(response.result.addresses.len(), 1);
+        assert_eq!(response.result.addresses[0].public, public);
+        let requests = server.requests();
+        assert_eq!(
+            mock::method(&requests[0]),
+            ("address".to_string(), json!({"address": public}))
+        );
+        assert_eq!(mock::method(&requests[1]).0, "all-addresses");
+    }
+
+    #[test]
+    fn address_types() {
+        let cases = [
+            (
+                "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q",
+                AddressType::Factoid,
+            ),
+            (
+                "Fs3E9gV6DXsYzf7Fqx1fVBQPQXV695eP3k5XbmHEZVRLkMdD9qCK",
+                AddressType::FactoidSecret,
+            ),
+            (
+                "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK",
+                AddressType::EntryCredit,
+            ),
+            (
+                "Es3LS7zYa9DSzZuUC14HDpMinehmzz61JG1XFY62rX5pVDenH8Pk",
+                AddressType::EcSecret,
+            ),
+            (
+                "idpub2g25nPNZ2kf6KGTjthYdHT3nykDbwEUEPyGJ52fo55SHwtAvLA",
+                AddressType::IdentityPublic,
+            ),
+            (
+                "idsec2rWrfNTD1x9HPPesA3fz8dmMNZdjmSBULHx8VTXE1J4D9icmAK",
+                AddressType::IdentitySecret,
+            ),
+        ];
+        for (address, expected) in cases.iter() {
+            assert_eq!(validate_address(address).unwrap(), *expected);
+        }
+    }
+
+    #[test]
+    fn validate_corrupted_address() {
+        let corrupted = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqL";
+        let err = validate_address(corrupted).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FetchError>(),
+            Some(FetchError::InvalidAddress(_))
+        ));
+    }
+
+    const PAIRS: [(&str, &str); 2] = [
+        (
+            "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q",
+            "646f3e8750c550e4582eca5047546ffef89c13a175985e320232bacac81cc428",
+        ),
+        (
+            "FA3cih2o2tjEUsnnFR4jX1tQXPpSXFwsp3rhVp6odL5PNCHWvZV1",
+            "d929b10b488382de5cccaa2a47b2a77ac04fd78926c67eb264615fc388063ab9",
+        ),
+    ];
+
+    #[test]
+    fn rcd_round_trip() {
+        for (user, rcd) in PAIRS.iter() {
+            assert_eq!(user_to_rcd(user).unwrap(), *rcd);
+            assert_eq!(rcd_to_user(rcd).unwrap(), *user);
+        }
+    }
+
+    #[test]
+    fn bad_checksum() {
+        let corrupted = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1R";
+        assert!(user_to_rcd(corrupted).is_err());
+    }
+
+    #[test]
+    fn wrong_prefix() {
+        let ec_address = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+        assert!(user_to_rcd(ec_address).is_err());
+    }
+}
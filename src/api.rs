@@ -1,18 +1,34 @@
 //! The api client module holds the Factom struct and request client.
 use super::*;
+use clock::{Clock, SystemClock};
 use constants::*;
+use dns::DnsResolver;
 use url::Url;
 
 use http::Uri;
+use std::env;
 use std::num::Wrapping;
+use std::time::Duration;
 
 /// Main struct from which API requests are built
-/// * client holds the hyper http client with a https connector
+/// * client holds the hyper http client with a https connector, it is built once
+/// and shared between clones so pooled connections are reused across calls
 /// * factomd/walletd/debug hold the request builders to which a json body
 /// is added
 /// * uri is the current uri locations
 /// * id is the json-rpc id field as a wrapped usize,
 /// it can be incremented without risking overflow
+/// * keep_alive determines whether connections are reused between requests
+/// * clock is the time source used when polling or waiting
+/// * max_response_bytes caps the size of a response body that will be read
+/// * basic_auth is the authorization header value sent with every request
+/// * compress_requests gzip encodes request bodies when enabled
+/// * connect_timeout limits how long establishing a connection may take
+/// * read_timeout limits how long a response may take once a request is sent
+/// * timeout limits how long a whole call may take, from connecting to reading
+/// the response body
+/// * dns_threads is the number of dedicated threads resolving hostnames, when
+/// unset lookups run on the runtime's blocking thread pool
 #[derive(Debug)]
 pub struct Factom {
     pub client: HttpsClient,
@@ -20,6 +36,15 @@ pub struct Factom {
     pub walletd_uri: Rc<Uri>,
     pub debug_uri: Rc<Uri>,
     pub id: Wrapping<usize>,
+    pub keep_alive: bool,
+    pub clock: Rc<dyn Clock>,
+    pub max_response_bytes: usize,
+    pub basic_auth: Option<Rc<String>>,
+    pub compress_requests: bool,
+    pub connect_timeout: Option<Duration>,
+    pub read_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
+    pub dns_threads: Option<usize>,
 }
 
 impl Factom {
@@ -40,13 +65,7 @@ impl Factom {
         let factomd_uri = parse_uri(FACTOMD_DEFAULT);
         let walletd_uri = parse_uri(WALLETD_DEFAULT);
         let debug_uri = parse_debug_uri(FACTOMD_DEFAULT);
-        Factom {
-            client: new_client(),
-            factomd_uri,
-            walletd_uri,
-            debug_uri,
-            id: Wrapping(ID),
-        }
+        Factom::from_uris(factomd_uri, walletd_uri, debug_uri)
     }
 
     /// Creates a factom struct using open node for factomd and a local wallet in
@@ -58,13 +77,7 @@ impl Factom {
         let factomd_uri = parse_uri(OPENNODE_URI);
         let walletd_uri = parse_uri(WALLETD_DEFAULT);
         let debug_uri = parse_debug_uri(OPENNODE_URI);
-        Factom {
-            client: new_client(),
-            factomd_uri,
-            walletd_uri,
-            debug_uri,
-            id: Wrapping(ID),
-        }
+        Factom::from_uris(factomd_uri, walletd_uri, debug_uri)
     }
 
     /// Creates a factom struct using the testnet open node for factomd and a
@@ -76,13 +89,7 @@ impl Factom {
         let factomd_uri = parse_uri(DEV_OPENNODE_URI);
         let walletd_uri = parse_uri(WALLETD_DEFAULT);
         let debug_uri = parse_debug_uri(DEV_OPENNODE_URI);
-        Factom {
-            client: new_client(),
-            factomd_uri,
-            walletd_uri,
-            debug_uri,
-            id: Wrapping(ID),
-        }
+        Factom::from_uris(factomd_uri, walletd_uri, debug_uri)
     }
 
     /// Creates a factom struct the provided custom hosts. Debug functions will
@@ -100,13 +107,266 @@ impl Factom {
         let factomd_uri = parse_uri(factomd);
         let walletd_uri = parse_uri(walletd);
         let debug_uri = parse_debug_uri(factomd);
-        Factom {
-            client: new_client(),
-            factomd_uri,
-            walletd_uri,
-            debug_uri,
-            id: Wrapping(ID),
-        }
+        Factom::from_uris(factomd_uri, walletd_uri, debug_uri)
+    }
+
+    /// Fallible version of custom_node, returns a FetchError::InvalidUri instead
+    /// of panicking if either host cannot be parsed.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let client = Factom::try_custom_node("https://api.factomd.net", "not a host");
+    /// assert!(client.is_err());
+    /// ```
+    pub fn try_custom_node(factomd: &str, walletd: &str) -> Result<Factom> {
+        let factomd_uri = try_parse_uri(factomd)?;
+        let walletd_uri = try_parse_uri(walletd)?;
+        let debug_uri = try_parse_debug_uri(factomd)?;
+        Ok(Factom::from_uris(factomd_uri, walletd_uri, debug_uri))
+    }
+
+    /// Creates a factom struct with factomd and walletd on the same host but at
+    /// the given ports, replacing any port already in the host. Useful when the
+    /// daemons run on non-default ports, the defaults being 8088 for factomd and
+    /// 8089 for walletd. Debug functions will use the factomd port.
+    /// Panics if the host cannot be parsed or can't be given a port.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let client = Factom::from_host_with_ports("https://node.example", 443, 8443);
+    /// assert_eq!(client.factomd_uri.to_string(), "https://node.example/v2");
+    /// assert_eq!(client.walletd_uri.to_string(), "https://node.example:8443/v2");
+    /// ```
+    pub fn from_host_with_ports(host: &str, factomd_port: u16, wallet_port: u16) -> Factom {
+        Factom::try_from_host_with_ports(host, factomd_port, wallet_port).expect("Parsing Uri")
+    }
+
+    /// Fallible version of from_host_with_ports, returns a FetchError::InvalidUri
+    /// instead of panicking if the host cannot be parsed or given a port.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let client = Factom::try_from_host_with_ports("mailto:node", 8088, 8089);
+    /// assert!(client.is_err());
+    /// ```
+    pub fn try_from_host_with_ports(
+        host: &str,
+        factomd_port: u16,
+        wallet_port: u16,
+    ) -> Result<Factom> {
+        let factomd = with_port(host, factomd_port)?;
+        let walletd = with_port(host, wallet_port)?;
+        Factom::try_custom_node(&factomd, &walletd)
+    }
+
+    /// Creates a factom struct configured from the environment, falling back to
+    /// the local defaults for any variable which is unset.
+    /// * FACTOMD_URL: factomd host, debug functions also use this host
+    /// * WALLETD_URL: walletd host
+    /// * FACTOM_BASIC_AUTH: optional credentials in the form user:password
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// std::env::set_var("FACTOMD_URL", "https://api.factomd.net");
+    /// let client = Factom::from_env().unwrap();
+    /// assert_eq!(client.factomd_uri.to_string(), "https://api.factomd.net/v2");
+    /// ```
+    pub fn from_env() -> Result<Factom> {
+        Factom::from_vars(
+            env::var(FACTOMD_URL_VAR).ok(),
+            env::var(WALLETD_URL_VAR).ok(),
+            env::var(BASIC_AUTH_VAR).ok(),
+        )
+    }
+
+    fn from_vars(
+        factomd: Option<String>,
+        walletd: Option<String>,
+        auth: Option<String>,
+    ) -> Result<Factom> {
+        let factomd = factomd.unwrap_or_else(|| FACTOMD_DEFAULT.to_string());
+        let walletd = walletd.unwrap_or_else(|| WALLETD_DEFAULT.to_string());
+        let client = Factom::try_custom_node(&factomd, &walletd)?;
+        Ok(match auth {
+            Some(credentials) => client.with_basic_auth(&credentials),
+            None => client,
+        })
+    }
+
+    /// Sends basic auth credentials, given in the form user:password, with
+    /// every request.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let client = Factom::new().with_basic_auth("user:password");
+    /// assert_eq!(client.basic_auth.unwrap().as_str(), "Basic dXNlcjpwYXNzd29yZA==");
+    /// ```
+    pub fn with_basic_auth(mut self, credentials: &str) -> Factom {
+        let header = format!("Basic {}", base64::encode(credentials));
+        self.basic_auth = Some(Rc::new(header));
+        self
+    }
+
+    /// Gzip encodes request bodies and sends a `Content-Encoding: gzip` header
+    /// when enabled, disabled by default. This can reduce the size of large
+    /// reveals but factomd and walletd don't accept compressed bodies, only
+    /// enable it for proxies or gateways which decompress requests.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let client = Factom::new().with_request_compression(true);
+    /// assert!(client.compress_requests);
+    /// ```
+    pub fn with_request_compression(mut self, enabled: bool) -> Factom {
+        self.compress_requests = enabled;
+        self
+    }
+
+    /// Sets how long establishing a connection to a daemon may take, requests
+    /// to an unreachable host then fail with a FetchError::ConnectTimeout. No
+    /// timeout is applied by default.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    /// use std::time::Duration;
+    ///
+    /// let client = Factom::open_node().with_connect_timeout(Duration::from_secs(5));
+    /// assert_eq!(client.connect_timeout, Some(Duration::from_secs(5)));
+    /// ```
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Factom {
+        self.connect_timeout = Some(timeout);
+        self.client = new_client(self.keep_alive, self.connect_timeout, self.dns_threads);
+        self
+    }
+
+    /// Resolves hostnames on the given number of dedicated threads rather than
+    /// the runtime's blocking thread pool, which bounds how many lookups run at
+    /// once. The threads are shared by clones and exit when the last is dropped.
+    /// Panics if the number of threads is zero.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let client = Factom::open_node().with_dns_threads(2);
+    /// assert_eq!(client.dns_threads, Some(2));
+    /// ```
+    pub fn with_dns_threads(mut self, threads: usize) -> Factom {
+        self.dns_threads = Some(threads);
+        self.client = new_client(self.keep_alive, self.connect_timeout, self.dns_threads);
+        self
+    }
+
+    /// Sets how long a response, including its body, may take to arrive once a
+    /// request is sent. Slow responses fail with a FetchError::ReadTimeout. No
+    /// timeout is applied by default. Requires the tokio or async-std runtime
+    /// feature for its timer.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    /// use std::time::Duration;
+    ///
+    /// let client = Factom::open_node().with_read_timeout(Duration::from_secs(30));
+    /// assert_eq!(client.read_timeout, Some(Duration::from_secs(30)));
+    /// ```
+    #[cfg(any(feature = "default", feature = "async-std-runtime"))]
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Factom {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how long a whole call may take, covering connecting, sending the
+    /// request and reading the response. Calls which take longer fail with a
+    /// FetchError::Timeout, so a hung node can't block a task indefinitely.
+    /// Calls are unbounded by default. Requires the tokio or async-std runtime
+    /// feature for its timer.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    /// use std::time::Duration;
+    ///
+    /// let client = Factom::open_node().with_timeout(Duration::from_secs(60));
+    /// assert_eq!(client.timeout, Some(Duration::from_secs(60)));
+    /// ```
+    #[cfg(any(feature = "default", feature = "async-std-runtime"))]
+    pub fn with_timeout(mut self, timeout: Duration) -> Factom {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Enables or disables connection keep-alive, enabled by default. When
+    /// disabled idle connections are not pooled and a `Connection: close` header
+    /// is sent with every request, this can help with proxies which misbehave
+    /// with persistent connections.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let client = Factom::open_node().with_keep_alive(false);
+    /// assert!(!client.keep_alive);
+    /// ```
+    pub fn with_keep_alive(mut self, enabled: bool) -> Factom {
+        self.keep_alive = enabled;
+        self.client = new_client(enabled, self.connect_timeout, self.dns_threads);
+        self
+    }
+
+    /// Replaces the api path used for factomd and walletd, which defaults to
+    /// `/v2`. Useful behind gateways which route json-rpc on a different path or
+    /// require a trailing slash. Debug functions keep using the debug path.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let client = Factom::custom_node("https://gateway.example", "http://localhost:8089")
+    ///   .with_path_suffix("/api/v2/");
+    /// assert_eq!(client.factomd_uri.to_string(), "https://gateway.example/api/v2/");
+    /// assert_eq!(client.walletd_uri.to_string(), "http://localhost:8089/api/v2/");
+    /// ```
+    pub fn with_path_suffix(mut self, path: &str) -> Factom {
+        self.factomd_uri = inner_parse_uri(&self.factomd_uri.to_string(), path);
+        self.walletd_uri = inner_parse_uri(&self.walletd_uri.to_string(), path);
+        self
+    }
+
+    /// Sets the maximum number of bytes read from a response body, larger
+    /// responses are aborted with a FetchError::ResponseTooLarge. Defaults to
+    /// 64 MB.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let client = Factom::open_node().with_max_response_bytes(1024 * 1024);
+    /// assert_eq!(client.max_response_bytes, 1024 * 1024);
+    /// ```
+    pub fn with_max_response_bytes(mut self, limit: usize) -> Factom {
+        self.max_response_bytes = limit;
+        self
+    }
+
+    /// Replaces the clock used when polling or waiting, this is mainly useful to
+    /// drive time deterministically in tests with a clock::ManualClock.
+    pub fn with_clock(mut self, clock: Rc<dyn Clock>) -> Factom {
+        self.clock = clock;
+        self
     }
 
     /// Increments the json-rpc id by one. Will wrap around to zero if it goes
@@ -121,14 +381,55 @@ impl Factom {
     }
 }
 
+impl Factom {
+    fn from_uris(factomd_uri: Rc<Uri>, walletd_uri: Rc<Uri>, debug_uri: Rc<Uri>) -> Factom {
+        Factom {
+            client: new_client(true, None, None),
+            factomd_uri,
+            walletd_uri,
+            debug_uri,
+            id: Wrapping(ID),
+            keep_alive: true,
+            clock: Rc::new(SystemClock),
+            max_response_bytes: MAX_RESPONSE_BYTES,
+            basic_auth: None,
+            compress_requests: false,
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
+            dns_threads: None,
+        }
+    }
+}
+
 /// Creates a https client, this is placed in the Factom struct and is responsible
-/// for making network requests
-fn new_client() -> HttpsClient {
-    let connector = HttpsConnector::new();
-    let client = Client::builder().build::<_, hyper::Body>(connector);
+/// for making network requests. Idle connections are only pooled with keep_alive.
+fn new_client(
+    keep_alive: bool,
+    connect_timeout: Option<Duration>,
+    dns_threads: Option<usize>,
+) -> HttpsClient {
+    let mut http = HttpConnector::new_with_resolver(DnsResolver::new(dns_threads));
+    http.enforce_http(false);
+    http.set_connect_timeout(connect_timeout);
+    let connector = HttpsConnector::new_with_connector(http);
+    let mut builder = Client::builder();
+    if !keep_alive {
+        builder.pool_max_idle_per_host(0);
+    }
+    #[cfg(all(feature = "async-std-runtime", not(feature = "default")))]
+    builder.executor(crate::runtime::AsyncStd);
+    let client = builder.build::<_, hyper::Body>(connector);
     Rc::new(client)
 }
 
+impl Default for Factom {
+    /// Equivalent to Factom::new(), using the local default hosts
+    fn default() -> Self {
+        Factom::new()
+    }
+}
+
 impl Clone for Factom {
     fn clone(&self) -> Self {
         let client = Rc::clone(&self.client);
@@ -138,10 +439,27 @@ impl Clone for Factom {
             walletd_uri: Rc::clone(&self.walletd_uri),
             debug_uri: Rc::clone(&self.debug_uri),
             id: self.id,
+            keep_alive: self.keep_alive,
+            clock: Rc::clone(&self.clock),
+            max_response_bytes: self.max_response_bytes,
+            basic_auth: self.basic_auth.clone(),
+            compress_requests: self.compress_requests,
+            connect_timeout: self.connect_timeout,
+            read_timeout: self.read_timeout,
+            timeout: self.timeout,
+            dns_threads: self.dns_threads,
         }
     }
 }
 
+/// Returns the host with its port replaced
+fn with_port(host: &str, port: u16) -> Result<String> {
+    let invalid = || FetchError::InvalidUri(host.to_string());
+    let mut url = Url::parse(host).map_err(|_| invalid())?;
+    url.set_port(Some(port)).map_err(|_| invalid())?;
+    Ok(String::from(url))
+}
+
 /// Parses the host and adds the debug path if not already included
 /// Panics with a ParseError if provided with an invalid url
 pub fn parse_debug_uri(host: &str) -> Rc<Uri> {
@@ -164,11 +482,28 @@ pub fn parse_uri(host: &str) -> Rc<Uri> {
     inner_parse_uri(host, API_VERSION)
 }
 
+/// Fallible version of parse_debug_uri, returns a FetchError::InvalidUri if
+/// provided with an invalid url
+pub fn try_parse_debug_uri(host: &str) -> Result<Rc<Uri>> {
+    try_inner_parse_uri(host, DEBUG)
+}
+
+/// Fallible version of parse_uri, returns a FetchError::InvalidUri if provided
+/// with an invalid url
+pub fn try_parse_uri(host: &str) -> Result<Rc<Uri>> {
+    try_inner_parse_uri(host, API_VERSION)
+}
+
 fn inner_parse_uri(host: &str, path: &str) -> Rc<Uri> {
-    let mut url = Url::parse(host).expect("Parsing Url");
+    try_inner_parse_uri(host, path).expect("Parsing Uri")
+}
+
+fn try_inner_parse_uri(host: &str, path: &str) -> Result<Rc<Uri>> {
+    let invalid = || FetchError::InvalidUri(host.to_string());
+    let mut url = Url::parse(host).map_err(|_| invalid())?;
     url.set_path(path);
-    let output: Uri = url.into_string().parse().expect("Parsing Uri");
-    Rc::new(output)
+    let output: Uri = String::from(url).parse().map_err(|_| invalid())?;
+    Ok(Rc::new(output))
 }
 
 // Uri parsing tests, confirm that even when given an incomplete host+path the
@@ -193,4 +528,126 @@ mod tests {
         let test_uri = parse_debug_uri("http://host");
         assert_eq!(test_uri, Rc::new(Uri::from_static("http://host/debug")));
     }
+
+    #[test]
+    fn from_vars_defaults() {
+        let client = Factom::from_vars(None, None, None).unwrap();
+        assert_eq!(client.factomd_uri, parse_uri(FACTOMD_DEFAULT));
+        assert_eq!(client.walletd_uri, parse_uri(WALLETD_DEFAULT));
+        assert_eq!(client.debug_uri, parse_debug_uri(FACTOMD_DEFAULT));
+        assert!(client.basic_auth.is_none());
+    }
+
+    #[test]
+    fn from_vars_set() {
+        let client = Factom::from_vars(
+            Some("https://factomd.example.com".to_string()),
+            Some("http://walletd.example.com:9000".to_string()),
+            Some("user:password".to_string()),
+        )
+        .unwrap();
+        let factomd = Uri::from_static("https://factomd.example.com/v2");
+        let walletd = Uri::from_static("http://walletd.example.com:9000/v2");
+        assert_eq!(client.factomd_uri, Rc::new(factomd));
+        assert_eq!(client.walletd_uri, Rc::new(walletd));
+        let auth = client.basic_auth.expect("Basic auth header");
+        assert_eq!(auth.as_str(), "Basic dXNlcjpwYXNzd29yZA==");
+    }
+
+    #[test]
+    fn default_is_local_node() {
+        let client = Factom::default();
+        assert_eq!(client.factomd_uri, parse_uri(FACTOMD_DEFAULT));
+        assert_eq!(client.walletd_uri, parse_uri(WALLETD_DEFAULT));
+        assert_eq!(client.debug_uri, parse_debug_uri(FACTOMD_DEFAULT));
+    }
+
+    #[test]
+    fn client_shared_between_calls() {
+        let body = mock::result(json!({
+            "directoryblockheight": 1,
+            "leaderheight": 1,
+            "entryblockheight": 1,
+            "entryheight": 1
+        }));
+        let server = mock::serve(vec![&body, &body]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let built = Rc::clone(&client.client);
+        let copy = client.clone().with_read_timeout(Duration::from_secs(5));
+        fetch(async {
+            factomd::heights(&client).await.unwrap();
+            factomd::heights(&copy).await.unwrap();
+        });
+        assert!(Rc::ptr_eq(&built, &client.client));
+        assert!(Rc::ptr_eq(&built, &copy.client));
+        server.requests();
+    }
+
+    #[test]
+    fn host_with_ports() {
+        let client = Factom::from_host_with_ports("http://localhost", 8088, 8089);
+        assert_eq!(client.factomd_uri, parse_uri(FACTOMD_DEFAULT));
+        assert_eq!(client.walletd_uri, parse_uri(WALLETD_DEFAULT));
+        assert_eq!(client.debug_uri, parse_debug_uri(FACTOMD_DEFAULT));
+
+        let client = Factom::from_host_with_ports("http://proxy.example:80", 9088, 9089);
+        let factomd = Uri::from_static("http://proxy.example:9088/v2");
+        let walletd = Uri::from_static("http://proxy.example:9089/v2");
+        let debug = Uri::from_static("http://proxy.example:9088/debug");
+        assert_eq!(client.factomd_uri, Rc::new(factomd));
+        assert_eq!(client.walletd_uri, Rc::new(walletd));
+        assert_eq!(client.debug_uri, Rc::new(debug));
+    }
+
+    #[test]
+    fn host_with_ports_errors() {
+        for host in &["not a host", "mailto:node"] {
+            let err = Factom::try_from_host_with_ports(host, 8088, 8089).unwrap_err();
+            match err.downcast_ref::<FetchError>() {
+                Some(FetchError::InvalidUri(invalid)) => assert_eq!(invalid, host),
+                _ => panic!("Expected an InvalidUri error"),
+            }
+        }
+    }
+
+    #[test]
+    fn dynamic_hosts_freed() {
+        for port in 10_000..10_100 {
+            let host = format!("http://node{}.example", port);
+            let client = Factom::from_host_with_ports(&host, port, port + 1);
+            let factomd = Rc::clone(&client.factomd_uri);
+            let walletd = Rc::clone(&client.walletd_uri);
+            assert_eq!(factomd.port_u16(), Some(port));
+            drop(client);
+            assert_eq!(Rc::strong_count(&factomd), 1);
+            assert_eq!(Rc::strong_count(&walletd), 1);
+        }
+    }
+
+    #[test]
+    fn call_with_dns_threads() {
+        let body = mock::result(json!({
+            "directoryblockheight": 1,
+            "leaderheight": 1,
+            "entryblockheight": 1,
+            "entryheight": 1
+        }));
+        let server = mock::serve(vec![&body]);
+        // Ip hosts skip resolution, so the mock is reached by name
+        let host = server.url.replace("127.0.0.1", "localhost");
+        let client = Factom::custom_node(&host, &host).with_dns_threads(2);
+        let response = fetch(factomd::heights(&client)).unwrap();
+        assert_eq!(response.result.leaderheight, 1);
+        assert_eq!(client.dns_threads, Some(2));
+        server.requests();
+    }
+
+    #[test]
+    fn invalid_host_errors() {
+        let err = Factom::try_custom_node("not a host", WALLETD_DEFAULT).unwrap_err();
+        match err.downcast_ref::<FetchError>() {
+            Some(FetchError::InvalidUri(host)) => assert_eq!(host, "not a host"),
+            _ => panic!("Expected an InvalidUri error"),
+        }
+    }
 }
@@ -1,7 +1,8 @@
 //! For balance related functions.
 use super::*;
 
-/// Return its current balance for a specific entry credit address.
+/// Return its current balance for a specific entry credit address. This is a
+/// factomd call, the address doesn't need to be in the wallet.
 /// # Example
 /// ```
 /// use factom::*;
@@ -10,7 +11,7 @@ use super::*;
 /// async fn main() {
 ///   let client = Factom::testnet_node();
 ///   let ec_address = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
-///   let response = balance::multiple_ec_balances(&client, vec![ec_address])
+///   let response = balance::entry_credit_balance(&client, ec_address)
 ///                             .await
 ///                             .expect("Fetching query");
 ///   dbg!(&response);
@@ -25,7 +26,8 @@ pub async fn entry_credit_balance(api: &Factom, address: &str) -> Result<ApiResp
 }
 
 ///  This call returns the number of Factoshis (Factoids *10^-8) that are
-/// currently available at the address specified.
+/// currently available at the address specified. This is a factomd call, the
+/// address doesn't need to be in the wallet.
 ///
 /// # Example
 /// ```
@@ -92,12 +94,13 @@ pub async fn factoid_balance(api: &Factom, address: &str) -> Result<ApiResponse<
 ///                             .await
 ///                             .expect("Fetching query");
 ///   assert!(response.success());
+///   assert_eq!(response.result.balances.len(), 1);
 /// }
 /// ```
 pub async fn multiple_ec_balances(
     api: &Factom,
     addresses: Vec<&str>,
-) -> Result<ApiResponse<Balances>> {
+) -> Result<ApiResponse<MultipleBalances>> {
     let mut req = ApiRequest::new("multiple-ec-balances");
     req.params.insert("addresses".to_string(), json!(addresses));
     let response = factomd_call(api, req).await;
@@ -153,7 +156,7 @@ pub async fn multiple_ec_balances(
 pub async fn multiple_fct_balances(
     api: &Factom,
     addresses: Vec<&str>,
-) -> Result<ApiResponse<Balances>> {
+) -> Result<ApiResponse<MultipleBalances>> {
     let mut req = ApiRequest::new("multiple-fct-balances");
     req.params.insert("addresses".to_string(), json!(addresses));
     let response = factomd_call(api, req).await;
@@ -183,3 +186,50 @@ pub struct Balances {
     #[serde(default)]
     pub err: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiple_balances_per_address() {
+        let body = mock::result(json!({"currentheight": 220010, "lastsavedheight": 220009,
+            "balances": [{"ack": 10, "saved": 5, "err": ""}, {"ack": 0, "saved": 0,
+            "err": "Address has not had a transaction"}, {"ack": 0, "saved": 0,
+            "err": "Error decoding address"}]}));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let addresses = vec![
+            "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q",
+            "FA3cih2o2tjEUsnnFR4jX1tQXPpSXFwsp3rhVp6odL5PNCHWvZV1",
+            "not an address",
+        ];
+        let response = fetch(multiple_fct_balances(&client, addresses.clone())).unwrap();
+        assert_eq!(response.result.currentheight, 220010);
+        assert_eq!(response.result.balances.len(), addresses.len());
+        assert_eq!(response.result.balances[0].ack, 10);
+        assert_eq!(response.result.balances[2].err, "Error decoding address");
+        let (_, params) = mock::method(&server.requests()[0]);
+        assert_eq!(params["addresses"], json!(addresses));
+    }
+
+    #[test]
+    fn balances_from_factomd() {
+        let fct = mock::result(json!({"balance": 2_000_000_000}));
+        let ec = mock::result(json!({"balance": 50}));
+        let server = mock::serve(vec![&fct, &ec]);
+        let client = Factom::custom_node(&server.url, &mock::closed_port());
+        let address = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+        let response = fetch(factoid_balance(&client, address)).unwrap();
+        assert_eq!(response.result.balance, 2_000_000_000);
+        let address = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+        let response = fetch(entry_credit_balance(&client, address)).unwrap();
+        assert_eq!(response.result.balance, 50);
+        let methods: Vec<String> = server
+            .requests()
+            .iter()
+            .map(|r| mock::method(r).0)
+            .collect();
+        assert_eq!(methods, vec!["factoid-balance", "entry-credit-balance"]);
+    }
+}
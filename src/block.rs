@@ -1,10 +1,38 @@
 //! Contains all api methods which query block data
 use super::*;
+use futures::stream::{self, Stream, StreamExt};
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+
+/// A directory block height, used by the functions reading blocks at a height
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Height(pub u32);
+
+impl From<u32> for Height {
+    fn from(height: u32) -> Self {
+        Height(height)
+    }
+}
+
+impl From<Height> for u32 {
+    fn from(height: Height) -> Self {
+        height.0
+    }
+}
+
+impl Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
 
 /// Anchortype is a required argument in the anchors function
 pub enum AnchorType {
     Hash(String),
-    Height(usize),
+    Height(Height),
 }
 
 /// Retrieve administrative blocks for any given height.
@@ -26,7 +54,7 @@ pub enum AnchorType {
 /// #[tokio::main]
 /// async fn main() {
 ///   let client = Factom::open_node();
-///   let height = 220000;
+///   let height = Height(220000);
 ///   let prevbackrefhash = "dbe20345a773a593d06cce65cf68d976011063208d54927433fac3c2b10f06b2";
 ///   let response = block::ablock_by_height(&client, height)
 ///                             .await
@@ -36,7 +64,7 @@ pub enum AnchorType {
 /// ```
 pub async fn ablock_by_height(
     api: &Factom,
-    height: u32,
+    height: Height,
 ) -> Result<ApiResponse<ABlockHeightResult>> {
     let mut req = ApiRequest::new("ablock-by-height");
     req.params.insert("height".to_string(), json!(height));
@@ -82,11 +110,12 @@ pub async fn admin_block(api: &Factom, keymr: &str) -> Result<ApiResponse<ABlock
 /// #[tokio::main]
 /// async fn main() {
 ///   let client = Factom::open_node();
-///   let directoryblockheight = 220000;
-///   let response = block::anchors(&client, block::AnchorType::Height(directoryblockheight))
+///   let height = Height(220000);
+///   let response = block::anchors(&client, block::AnchorType::Height(height))
 ///                                 .await
 ///                                 .expect("API Request");
-///    assert_eq!(response.result.directoryblockheight, directoryblockheight);
+///    assert_eq!(response.result.directoryblockheight, 220000);
+///    assert!(response.result.bitcoin.is_some());
 /// }
 /// ```
 pub async fn anchors(api: &Factom, target: AnchorType) -> Result<ApiResponse<Anchor>> {
@@ -111,7 +140,7 @@ pub async fn anchors(api: &Factom, target: AnchorType) -> Result<ApiResponse<Anc
 /// #[tokio::main]
 /// async fn main() {
 ///   let client = Factom::open_node();
-///   let height = 220000;
+///   let height = Height(220000);
 ///   let bodymr = "a575d8f07c725cd8c523c7881121dac330a29b6d5edcf2da0b0f7d2d5cbba256";
 ///   let response = block::dblock_by_height(&client, height)
 ///                             .await
@@ -122,7 +151,7 @@ pub async fn anchors(api: &Factom, target: AnchorType) -> Result<ApiResponse<Anc
 /// ```
 pub async fn dblock_by_height(
     api: &Factom,
-    height: u32,
+    height: Height,
 ) -> Result<ApiResponse<DBlockHeightResult>> {
     let mut req = ApiRequest::new("dblock-by-height");
     req.params.insert("height".to_string(), json!(height));
@@ -130,6 +159,116 @@ pub async fn dblock_by_height(
     parse(response).await
 }
 
+/// Retrieve the directory block which anchored a given entry. The entry's
+/// directory block height is found with the transaction call and the block at
+/// that height is then fetched.
+///
+/// Returns a FetchError::Unconfirmed if the entry has not yet been included in
+/// a directory block. Api errors from the transaction lookup are passed through
+/// in the returned ApiResponse.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let entryhash = "716526c3279184bca11fc453fa9c2ab2f4488a03c821ee107664c9052f01d733";
+///   let chainid = "a642a8674f46696cc47fdb6b65f9c87b2a19c5ea8123b3d2f0c13b6f33a9d5ef";
+///   let response = block::entry_directory_block(&client, entryhash)
+///                             .await
+///                             .expect("Request");
+///   assert!(response.result.dblock.dbentries.iter().any(|e| e.chainid == chainid));
+/// }
+/// ```
+pub async fn entry_directory_block(
+    api: &Factom,
+    entryhash: &str,
+) -> Result<ApiResponse<DBlockHeightResult>> {
+    let tx_response = tx::transaction(api, entryhash).await?;
+    if tx_response.is_err() {
+        return Ok(ApiResponse {
+            jsonrpc: tx_response.jsonrpc,
+            id: tx_response.id,
+            result: DBlockHeightResult::default(),
+            error: tx_response.error,
+        });
+    }
+    let height = tx_response.result.includedindirectoryblockheight;
+    if height < 0 {
+        return Err(FetchError::Unconfirmed(entryhash.to_string()).into());
+    }
+    dblock_by_height(api, Height(height as u32)).await
+}
+
+/// Lists the distinct chain ids with entry blocks in the directory blocks from
+/// start to end inclusive, in the order they first appear. The admin, entry
+/// credit and factoid block chains are left out. Blocks are fetched a few at a
+/// time and only the chain ids are kept.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let chains = block::all_chains(&client, Height(220000), Height(220010))
+///                             .await
+///                             .expect("Request");
+///   println!("{} chains written to", chains.len());
+/// }
+/// ```
+pub async fn all_chains(api: &Factom, start: Height, end: Height) -> Result<Vec<String>> {
+    let mut blocks = stream::iter(start.0..=end.0)
+        .map(|height| dblock_by_height(api, Height(height)))
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .boxed_local();
+    let mut seen = HashSet::new();
+    let mut chains = Vec::new();
+    while let Some(response) = blocks.next().await {
+        for dbentry in response?.api_result()?.dblock.dbentries {
+            if SYSTEM_CHAINS.contains(&dbentry.chainid.as_str()) {
+                continue;
+            }
+            if seen.insert(dbentry.chainid.clone()) {
+                chains.push(dbentry.chainid);
+            }
+        }
+    }
+    Ok(chains)
+}
+
+/// Compares the directory block keymr at a height on this node against a trusted
+/// reference node. A mismatch indicates this node is on a fork or otherwise
+/// desynced from the reference. Both nodes are queried concurrently.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let reference = Factom::open_node();
+///   let canonical = block::is_on_canonical_chain(&client, &reference, Height(220000))
+///                             .await
+///                             .expect("Request");
+///   assert!(canonical);
+/// }
+/// ```
+pub async fn is_on_canonical_chain(
+    api: &Factom,
+    reference: &Factom,
+    height: Height,
+) -> Result<bool> {
+    let (local, remote) = futures::join!(
+        dblock_by_height(api, height),
+        dblock_by_height(reference, height)
+    );
+    let local = local?.api_result()?;
+    let remote = remote?.api_result()?;
+    Ok(local.dblock.keymr == remote.dblock.keymr)
+}
+
 /// Every directory block has a KeyMR (Key Merkle Root), which can be used to
 /// retrieve it. The response will contain information that can be used to
 /// navigate through all transactions (entry and factoid) within that block. The
@@ -187,7 +326,7 @@ pub async fn directory_block_head(api: &Factom) -> Result<ApiResponse<DBlockHead
 /// #[tokio::main]
 /// async fn main() {
 ///   let client = Factom::open_node();
-///   let height = 220000;
+///   let height = Height(220000);
 ///   let bodyhash = "c4b5a9d81028c5a87e7ff1d5ee8e1446f337ac6c57eca185a533cadeb653acee";
 ///   let response = block::ecblock_by_height(&client, height)
 ///                             .await
@@ -198,7 +337,7 @@ pub async fn directory_block_head(api: &Factom) -> Result<ApiResponse<DBlockHead
 /// ```
 pub async fn ecblock_by_height(
     api: &Factom,
-    height: u32,
+    height: Height,
 ) -> Result<ApiResponse<EBlockHeightResult>> {
     let mut req = ApiRequest::new("ecblock-by-height");
     req.params.insert("height".to_string(), json!(height));
@@ -231,6 +370,65 @@ pub async fn entry_block(api: &Factom, keymr: &str) -> Result<ApiResponse<EBlock
     parse(response).await
 }
 
+/// Streams a chain's entry blocks in order, starting from the first entry block
+/// at or after the given directory block height. This is useful for re-indexing
+/// a chain from a known height.
+///
+/// The chain is walked back from its head until the start height is passed, the
+/// blocks are then yielded oldest first.
+/// # Example
+/// ```no_run
+/// use factom::*;
+/// use futures::stream::StreamExt;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let chainid = "a642a8674f46696cc47fdb6b65f9c87b2a19c5ea8123b3d2f0c13b6f33a9d5ef";
+///   let height = Height(220000);
+///   let mut blocks = block::entry_blocks_from_height(&client, chainid, height).boxed_local();
+///   while let Some(eblock) = blocks.next().await {
+///     dbg!(eblock.expect("Fetching EntryBlock").header);
+///   }
+/// }
+/// ```
+pub fn entry_blocks_from_height<'a>(
+    api: &'a Factom,
+    chainid: &'a str,
+    start_height: Height,
+) -> impl Stream<Item = Result<EBlock>> + 'a {
+    let walk = walk_entry_blocks(api, chainid, start_height);
+    stream::once(walk).flat_map(|walked| {
+        let blocks: Vec<Result<EBlock>> = match walked {
+            Ok(blocks) => blocks.into_iter().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        };
+        stream::iter(blocks)
+    })
+}
+
+/// Walks a chain back from its head collecting entry blocks at or above the
+/// start height, returned oldest first
+async fn walk_entry_blocks(
+    api: &Factom,
+    chainid: &str,
+    start_height: Height,
+) -> Result<Vec<EBlock>> {
+    let head = chain::chain_head(api, chainid).await?.api_result()?;
+    let mut keymr = head.chainhead;
+    let mut blocks = Vec::new();
+    while keymr != NULL_KEYMR {
+        let eblock = entry_block(api, &keymr).await?.api_result()?;
+        if Height(eblock.header.dbheight as u32) < start_height {
+            break;
+        }
+        keymr = eblock.header.prevkeymr.clone();
+        blocks.push(eblock);
+    }
+    blocks.reverse();
+    Ok(blocks)
+}
+
 /// Retrieve a specified entrycredit block given its merkle root key. The numbers
 /// are minute markers.
 /// # Example
@@ -286,7 +484,7 @@ pub async fn factoid_block(api: &Factom, keymr: &str) -> Result<ApiResponse<FBlo
 /// #[tokio::main]
 /// async fn main() {
 ///   let client = Factom::open_node();
-///   let height = 220000;
+///   let height = Height(220000);
 ///   let bodymr = "faa064a185a2c677404dcc24e9428e781a19350fc8f21c7daf3d18b1d3f91412";
 ///   let response = block::fblock_by_height(&client, height)
 ///                             .await
@@ -295,7 +493,7 @@ pub async fn factoid_block(api: &Factom, keymr: &str) -> Result<ApiResponse<FBlo
 ///   assert_eq!(response.result.fblock.bodymr, bodymr);
 /// }
 ///```
-pub async fn fblock_by_height(api: &Factom, height: u32) -> Result<ApiResponse<FBlockResult>> {
+pub async fn fblock_by_height(api: &Factom, height: Height) -> Result<ApiResponse<FBlockResult>> {
     let mut req = ApiRequest::new("fblock-by-height");
     req.params.insert("height".to_string(), json!(height));
     let response = factomd_call(api, req).await;
@@ -309,6 +507,7 @@ pub struct ABlockHeightResult {
     pub rawdata: String,
 }
 
+/// The lookuphash is the admin block's hash as listed in the directory block
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AblockHeight {
     pub header: Header,
@@ -317,12 +516,19 @@ pub struct AblockHeight {
     pub lookuphash: String,
 }
 
+/// Admin block entry, the fields present depend on the adminidtype. Fields not
+/// modelled here, such as those of server additions and removals, are kept in
+/// other.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ABHeightentry {
+    #[serde(default)]
+    pub adminidtype: Option<u8>,
     #[serde(default)]
     pub identityadminchainid: String,
     #[serde(default)]
     pub prevdbsig: Prevdbsig,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
 }
 
 /// admin block function
@@ -332,6 +538,7 @@ pub struct ABlockResult {
     pub rawdata: String,
 }
 
+/// The lookuphash is the admin block's hash as listed in the directory block
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ablock {
     pub header: Header,
@@ -352,11 +559,18 @@ pub struct Header {
     pub chainid: String,
 }
 
+/// Admin block entry, the fields present depend on the adminidtype. Fields not
+/// modelled here, such as those of server additions and removals, are kept in
+/// other.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Abentry {
+    #[serde(default)]
+    pub adminidtype: Option<u8>,
     pub identityadminchainid: Option<String>,
     pub prevdbsig: Option<Prevdbsig>,
     pub minutenumber: Option<u8>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -366,18 +580,25 @@ pub struct Prevdbsig {
     pub sig: String,
 }
 
-/// anchors function
+/// anchors function, the bitcoin and ethereum anchors are None until the
+/// directory block has been anchored on that chain
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Anchor {
     pub directoryblockheight: usize,
     pub directoryblockkeymr: String,
-    #[serde(default)]
-    pub bitcoin: Bitcoin,
-    pub ethereum: Ethereuem,
+    #[serde(default, deserialize_with = "utils::false_as_none")]
+    pub bitcoin: Option<Bitcoin>,
+    #[serde(default, deserialize_with = "utils::false_as_none")]
+    pub ethereum: Option<Ethereum>,
 }
 
+#[deprecated(note = "Renamed to Ethereum")]
+pub type Ethereuem = Ethereum;
+
+/// Ethereum anchor, dbheightmin and dbheightmax give the window of directory
+/// blocks covered by the windowmr merkle root
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Ethereuem {
+pub struct Ethereum {
     pub recordheight: usize,
     pub dbheightmax: usize,
     pub dbheightmin: usize,
@@ -518,7 +739,7 @@ pub struct EBlockHeader {
     pub blocksequencenumber: usize,
     pub chainid: String,
     pub prevkeymr: String,
-    pub timestamp: usize,
+    pub timestamp: i64,
     pub dbheight: usize,
 }
 
@@ -526,7 +747,7 @@ pub struct EBlockHeader {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Entrylist {
     pub entryhash: String,
-    pub timestamp: usize,
+    pub timestamp: i64,
 }
 
 /// entrycredit-block function
@@ -562,6 +783,19 @@ pub struct FBlockResult {
     pub rawdata: String,
 }
 
+impl FBlockResult {
+    /// Returns the block's transactions as the Factoidtransaction type used by
+    /// the transaction call. The coinbase transaction has no inputs, so its
+    /// inputs are None.
+    pub fn typed_transactions(&self) -> Vec<tx::Factoidtransaction> {
+        self.fblock
+            .transactions
+            .iter()
+            .map(Transaction::to_factoid_transaction)
+            .collect()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Fblock {
     pub bodymr: String,
@@ -580,13 +814,68 @@ pub struct Transaction {
     pub txid: String,
     pub blockheight: usize,
     pub millitimestamp: usize,
+    #[serde(default, deserialize_with = "utils::null_as_default")]
     pub inputs: Vec<Input>,
+    #[serde(default, deserialize_with = "utils::null_as_default")]
     pub outputs: Vec<Output>,
+    #[serde(default, deserialize_with = "utils::null_as_default")]
     pub outecs: Vec<::serde_json::Value>,
+    #[serde(default, deserialize_with = "utils::null_as_default")]
     pub rcds: Vec<String>,
+    #[serde(default, deserialize_with = "utils::null_as_default")]
     pub sigblocks: Vec<Sigblock>,
 }
 
+impl Transaction {
+    /// Converts into a tx::Factoidtransaction, entry credit outputs which don't
+    /// have the expected fields are skipped
+    fn to_factoid_transaction(&self) -> tx::Factoidtransaction {
+        let inputs: Vec<tx::Input> = self
+            .inputs
+            .iter()
+            .map(|i| tx::Input {
+                amount: i.amount,
+                address: i.address.clone(),
+                useraddress: i.useraddress.clone(),
+            })
+            .collect();
+        let outputs = self
+            .outputs
+            .iter()
+            .map(|o| tx::Output {
+                amount: o.amount,
+                address: o.address.clone(),
+                useraddress: o.useraddress.clone(),
+            })
+            .collect();
+        let outecs = self
+            .outecs
+            .iter()
+            .filter_map(|o| serde_json::from_value(o.clone()).ok())
+            .collect();
+        let sigblocks = self
+            .sigblocks
+            .iter()
+            .map(|s| tx::Sigblock {
+                signatures: s.signatures.clone(),
+            })
+            .collect();
+        tx::Factoidtransaction {
+            millitimestamp: self.millitimestamp,
+            inputs: if inputs.is_empty() {
+                None
+            } else {
+                Some(inputs)
+            },
+            outputs: Some(outputs),
+            outecs: Some(outecs),
+            rcds: self.rcds.clone(),
+            sigblocks,
+            blockheight: self.blockheight,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Input {
     pub amount: usize,
@@ -605,3 +894,162 @@ pub struct Output {
 pub struct Sigblock {
     pub signatures: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heights_compare() {
+        let height = Height::from(220000);
+        assert_eq!(height, Height(220000));
+        assert!(Height(219999) < height);
+        assert_eq!(u32::from(height), 220000);
+        assert_eq!(height.to_string(), "220000");
+        assert_eq!(json!(height), json!(220000));
+    }
+
+    #[test]
+    fn admin_entries_keep_unmodelled_fields() {
+        let idchain = "888888b2e7c7c3b9d5e4e5e3a2c8a3ae3b9c0cfdb1e0f4e1b7f3d0c8a4b3e2d1";
+        let admin_chain = SYSTEM_CHAINS[0];
+        let body = mock::result(json!({
+            "ablock": {
+                "header": {
+                    "prevbackrefhash": "aa",
+                    "dbheight": 220000,
+                    "headerexpansionsize": 0,
+                    "headerexpansionarea": "",
+                    "messagecount": 2,
+                    "bodysize": 131,
+                    "adminchainid": admin_chain,
+                    "chainid": admin_chain
+                },
+                "abentries": [
+                    {
+                        "adminidtype": 1,
+                        "identityadminchainid": idchain,
+                        "prevdbsig": {"pub": "bb", "sig": "cc"}
+                    },
+                    {"adminidtype": 5, "identitychainid": idchain, "dbheight": 220001}
+                ],
+                "backreferencehash": "dd",
+                "lookuphash": "ee"
+            },
+            "rawdata": "00"
+        }));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let ablock = fetch(ablock_by_height(&client, Height(220000)))
+            .unwrap()
+            .result
+            .ablock;
+        assert_eq!(ablock.lookuphash, "ee");
+        let signature = &ablock.abentries[0];
+        assert_eq!(signature.adminidtype, Some(1));
+        assert_eq!(signature.prevdbsig.sig, "cc");
+        assert!(signature.other.is_empty());
+        let added = &ablock.abentries[1];
+        assert_eq!(added.adminidtype, Some(5));
+        assert_eq!(added.other["identitychainid"], idchain);
+        assert_eq!(added.other["dbheight"], 220001);
+        server.requests();
+    }
+
+    #[test]
+    fn anchors_before_and_after_anchoring() {
+        let keymr = "7e6b8f8b2d1f0e7fdc8a3c5a5e3d6a1f0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e";
+        let pending = mock::result(json!({
+            "directoryblockheight": 250000,
+            "directoryblockkeymr": keymr,
+            "bitcoin": false,
+            "ethereum": null
+        }));
+        let anchored = mock::result(json!({
+            "directoryblockheight": 220000,
+            "directoryblockkeymr": keymr,
+            "bitcoin": {"transactionhash": "aa", "blockhash": "bb"},
+            "ethereum": {
+                "recordheight": 220010,
+                "dbheightmax": 220010,
+                "dbheightmin": 219000,
+                "windowmr": "cc",
+                "merklebranch": [],
+                "contractaddress": "0xdd",
+                "txid": "0xee",
+                "blockhash": "0xff",
+                "txindex": 3
+            }
+        }));
+        let server = mock::serve(vec![&pending, &anchored]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let response = fetch(anchors(&client, AnchorType::Height(Height(250000)))).unwrap();
+        assert_eq!(response.result.bitcoin, None);
+        assert_eq!(response.result.ethereum, None);
+        let response = fetch(anchors(&client, AnchorType::Hash(keymr.to_string()))).unwrap();
+        assert_eq!(response.result.bitcoin.unwrap().blockhash, "bb");
+        let ethereum = response.result.ethereum.unwrap();
+        assert_eq!(
+            (ethereum.dbheightmin, ethereum.dbheightmax),
+            (219000, 220010)
+        );
+        let requests = server.requests();
+        assert_eq!(mock::method(&requests[0]).1["height"], 250000);
+        assert_eq!(mock::method(&requests[1]).1["hash"], keymr);
+    }
+
+    #[test]
+    fn all_chains_deduplicated() {
+        let server = mock::serve_with(2, |request| {
+            let (_, params) = mock::method(request);
+            let chains = match params["height"].as_u64().unwrap() {
+                10 => vec![SYSTEM_CHAINS[0], "c1", "c2"],
+                _ => vec![SYSTEM_CHAINS[0], "c2", "c3"],
+            };
+            let dbentries: Vec<_> = chains
+                .iter()
+                .map(|c| json!({"chainid": c, "keymr": "00"}))
+                .collect();
+            mock::result(json!({
+                "dblock": {"header": {"version": 0, "networkid": 0, "bodymr": "",
+                    "prevkeymr": "", "prevfullhash": "", "timestamp": 0, "dbheight": 10,
+                    "blockcount": 3, "chainid": ""},
+                    "dbentries": dbentries, "dbhash": "", "keymr": ""},
+                "rawdata": ""
+            }))
+        });
+        let client = Factom::custom_node(&server.url, &server.url);
+        let chains = fetch(all_chains(&client, Height(10), Height(11))).unwrap();
+        assert_eq!(chains, vec!["c1", "c2", "c3"]);
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn fblock_typed_transactions() {
+        let address = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+        let fblock = json!({
+            "fblock": {"bodymr": "", "prevkeymr": "", "prevledgerkeymr": "", "exchrate": 1000,
+                "dbheight": 220000, "chainid": "", "keymr": "", "ledgerkeymr": "",
+                "transactions": [
+                    {"txid": "aa", "blockheight": 0, "millitimestamp": 1575574200000u64,
+                        "inputs": null, "outputs": [], "outecs": null, "rcds": null,
+                        "sigblocks": null},
+                    {"txid": "bb", "blockheight": 0, "millitimestamp": 1575574473000u64,
+                        "inputs": [{"amount": 1000012000, "address": "646f3e87",
+                            "useraddress": address}],
+                        "outputs": [{"amount": 1000000000, "address": "8a1b2c3d",
+                            "useraddress": address}],
+                        "outecs": [], "rcds": ["01ab"], "sigblocks": [{"signatures": ["cd"]}]}
+                ]},
+            "rawdata": ""
+        });
+        let result: FBlockResult = serde_json::from_value(fblock).unwrap();
+        let txs = result.typed_transactions();
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].inputs, None);
+        assert_eq!(txs[0].fee(), 0);
+        assert_eq!(txs[1].inputs.as_ref().unwrap()[0].useraddress, address);
+        assert_eq!(txs[1].fee(), 12000);
+        assert_eq!(txs[1].sigblocks[0].signatures, vec!["cd".to_string()]);
+    }
+}
@@ -3,6 +3,9 @@ use super::*;
 
 /// Return the keymr of the head of the chain for a chain ID (the unique hash
 /// created when the chain was created).
+///
+/// A chain created in the current block has no head yet, the chainhead is
+/// empty and chaininprocesslist is true until the block completes.
 /// # Example
 /// ```
 /// use factom::*;
@@ -22,7 +25,11 @@ pub async fn chain_head(client: &Factom, chainid: &str) -> Result<ApiResponse<Ch
     let mut req = ApiRequest::new("chain-head");
     req.params.insert("chainid".to_string(), json!(chainid));
     let response = requests::factomd_call(client, req).await;
-    parse(response).await
+    parse(response)
+        .await?
+        .require("chainhead", |head: &ChainHead| {
+            !head.chainhead.is_empty() || head.chaininprocesslist
+        })
 }
 
 /// Send a Chain Commit Message to factomd to create a new Chain.
@@ -77,6 +84,7 @@ pub async fn reveal_chain(api: &Factom, entry: &str) -> Result<ApiResponse<Revea
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChainHead {
     pub chainhead: String,
+    #[serde(default)]
     pub chaininprocesslist: bool,
 }
 
@@ -98,3 +106,45 @@ pub struct RevealChain {
     #[serde(alias = "chainidhash")]
     pub chainid: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_head_in_process_list() {
+        let body = mock::result(json!({"chainhead": "", "chaininprocesslist": true}));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let head = fetch(chain_head(&client, "aa")).unwrap();
+        assert!(head.result.chaininprocesslist);
+        let (method, params) = mock::method(&server.requests()[0]);
+        assert_eq!(method, "chain-head");
+        assert_eq!(params["chainid"], "aa");
+    }
+
+    #[test]
+    fn commit_chain_response() {
+        let body = mock::result(json!({"message": "Chain Commit Success",
+            "txid": "76e123d133a841fe3e08c5e3f3d392f8431f2d7668890c03f003f541efa8fc61",
+            "entryhash": "f5c956749fc3eba4acc60fd485fb100e601070a44fcce54ff358d60669854734",
+            "chainidhash": "f5c956749fc3eba4acc60fd485fb100e601070a44fcce54ff358d60669854734"}));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let commit = fetch(commit_chain(&client, "00")).unwrap().result;
+        assert_eq!(commit.message, "Chain Commit Success");
+        assert_eq!(commit.chainid, commit.entryhash);
+        assert_eq!(mock::method(&server.requests()[0]).0, "commit-chain");
+    }
+
+    #[test]
+    fn chain_head_missing_process_flag() {
+        let body = mock::result(json!({"chainhead": "cc"}));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let head = fetch(chain_head(&client, "aa")).unwrap();
+        assert_eq!(head.result.chainhead, "cc");
+        assert!(!head.result.chaininprocesslist);
+        server.requests();
+    }
+}
@@ -0,0 +1,141 @@
+//! Time source used by polling and waiting functions.
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+#[cfg(not(any(feature = "default", feature = "async-std-runtime")))]
+use std::sync::{Arc, Mutex};
+#[cfg(not(any(feature = "default", feature = "async-std-runtime")))]
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+/// Future returned by Clock::sleep
+pub type Sleep = Pin<Box<dyn Future<Output = ()>>>;
+
+/// A source of time held by the Factom struct. Functions which poll or wait use
+/// the clock rather than the system time directly so they can be driven
+/// deterministically in tests.
+pub trait Clock: Debug {
+    /// Returns the current instant
+    fn now(&self) -> Instant;
+    /// Returns a future which completes once the duration has elapsed
+    fn sleep(&self, duration: Duration) -> Sleep;
+}
+
+/// The default clock, backed by the system time and the runtime's timer
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    #[cfg(any(feature = "default", feature = "async-std-runtime"))]
+    fn sleep(&self, duration: Duration) -> Sleep {
+        use crate::runtime::{AsyncRuntime, DefaultRuntime};
+        DefaultRuntime::sleep(duration)
+    }
+
+    #[cfg(not(any(feature = "default", feature = "async-std-runtime")))]
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(ThreadSleep::new(duration))
+    }
+}
+
+/// Sleep used when no runtime feature is enabled. A helper thread waits out the
+/// duration and then wakes the task, so the executor is never blocked.
+#[cfg(not(any(feature = "default", feature = "async-std-runtime")))]
+struct ThreadSleep {
+    state: Arc<Mutex<SleepState>>,
+}
+
+#[cfg(not(any(feature = "default", feature = "async-std-runtime")))]
+#[derive(Default)]
+struct SleepState {
+    elapsed: bool,
+    waker: Option<Waker>,
+}
+
+#[cfg(not(any(feature = "default", feature = "async-std-runtime")))]
+impl ThreadSleep {
+    fn new(duration: Duration) -> ThreadSleep {
+        let state = Arc::new(Mutex::new(SleepState::default()));
+        let shared = Arc::clone(&state);
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let mut state = shared.lock().expect("Sleep state");
+            state.elapsed = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        ThreadSleep { state }
+    }
+}
+
+#[cfg(not(any(feature = "default", feature = "async-std-runtime")))]
+impl Future for ThreadSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let mut state = self.state.lock().expect("Sleep state");
+        if state.elapsed {
+            return Poll::Ready(());
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// A clock which only moves when told to. Sleeping advances the clock by the
+/// requested duration and completes immediately.
+///
+/// # Example
+/// ```
+/// use factom::*;
+/// use factom::clock::{Clock, ManualClock};
+/// use std::rc::Rc;
+/// use std::time::Duration;
+///
+/// let clock = Rc::new(ManualClock::new());
+/// let client = Factom::new().with_clock(clock.clone());
+/// let start = clock.now();
+/// clock.advance(Duration::from_secs(5));
+/// assert_eq!(clock.now() - start, Duration::from_secs(5));
+/// ```
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Cell<Instant>,
+}
+
+impl ManualClock {
+    /// Creates a manual clock starting at the current system time
+    pub fn new() -> ManualClock {
+        ManualClock {
+            now: Cell::new(Instant::now()),
+        }
+    }
+
+    /// Moves the clock forward by the given duration
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        self.advance(duration);
+        Box::pin(futures::future::ready(()))
+    }
+}
@@ -8,7 +8,22 @@ use std::collections::HashMap;
 /// API calls. To be safe, wait a few seconds after calling commit.
 ///
 /// Note: The firstentry fields are automatically hex encoded for the server to
-/// process.
+/// process, so pass the extids and content as plain strings. Hex encoding them
+/// beforehand would encode them twice.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let ecpub = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+///   let extids = vec!["My Chain", "Version 1"];
+///   let composed = compose::compose_chain(&client, extids, "Hello", ecpub).await.unwrap();
+///   chain::commit_chain(&client, &composed.result.commit.params.message).await.unwrap();
+///   chain::reveal_chain(&client, &composed.result.reveal.params.entry).await.unwrap();
+/// }
+/// ```
 pub async fn compose_chain(
     api: &Factom,
     extids: Vec<&str>,
@@ -37,7 +52,23 @@ pub async fn compose_chain(
 /// entry in factom. You must first call the commit-entry, then the reveal-entry
 /// API calls. To be safe, wait a few seconds after calling commit.
 ///
-/// Note: The entry fields are automatically hex encoded for the server to process.
+/// Note: The entry fields are automatically hex encoded for the server to process,
+/// so pass the extids and content as plain strings. The chainid is sent as is.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let ecpub = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+///   let chainid = "a642a8674f46696cc47fdb6b65f9c87b2a19c5ea8123b3d2f0c13b6f33a9d5ef";
+///   let query = compose::compose_entry(&client, chainid, vec!["Note"], "Hello", ecpub);
+///   let composed = query.await.unwrap();
+///   entry::commit_entry(&client, &composed.result.commit.params.message).await.unwrap();
+///   entry::reveal_entry(&client, &composed.result.reveal.params.entry).await.unwrap();
+/// }
+/// ```
 pub async fn compose_entry(
     api: &Factom,
     chainid: &str,
@@ -65,6 +96,30 @@ pub async fn compose_entry(
 /// string can be inputted into the factomd API factoid-submit to be sent to
 /// the network.
 ///
+/// The full workflow creates a named transaction in the wallet, adds inputs and
+/// outputs, lets walletd calculate the fee, signs, composes then submits.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let from = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+///   let to = "FA3cih2o2tjEUsnnFR4jX1tQXPpSXFwsp3rhVp6odL5PNCHWvZV1";
+///   let tx_name = "my-tx";
+///   tx::new_transaction(&client, tx_name).await.unwrap();
+///   tx::add_input(&client, tx_name, from, 100_000).await.unwrap();
+///   tx::add_output(&client, tx_name, to, 100_000).await.unwrap();
+///   tx::add_fee(&client, tx_name, from).await.unwrap();
+///   tx::sign_transaction(&client, tx_name).await.unwrap();
+///   let composed = compose::compose_transaction(&client, tx_name).await.unwrap();
+///   let tx_hex = &composed.result.params.transaction;
+///   let submitted = tx::factoid_submit(&client, tx_hex).await.unwrap();
+///   println!("Submitted {}", submitted.result.txid);
+/// }
+/// ```
+///
 /// See the examples folder for a demonstration of the full workflow.
 pub async fn compose_transaction(api: &Factom, tx_name: &str) -> Result<ApiResponse<ComposeTx>> {
     let mut req = ApiRequest::new("compose-transaction");
@@ -105,6 +160,25 @@ pub async fn compose_transaction(api: &Factom, tx_name: &str) -> Result<ApiRespo
 /// The response you receive is similar to the compose-entry response. You must
 /// first call the commit-entry, then the reveal-entry API calls. To be safe,
 /// wait a few seconds after calling commit.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let chainid = "3b69dabe22c014af9a9bc9dfa7917ce4602a03579597ddf184d8de56702512ae";
+///   let signer = "idpub2g25nPNZ2kf6KGTjthYdHT3nykDbwEUEPyGJ52fo55SHwtAvLA";
+///   let ecpub = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+///   let attributes = vec![("email", "info@factom.com")];
+///   let composed = compose::compose_id_attribute(
+///     &client, chainid, chainid, attributes, signer, chainid, ecpub, false
+///   ).await.unwrap();
+///   let commit = &composed.result.commit.params.message;
+///   entry::commit_entry(&client, commit).await.unwrap();
+/// }
+/// ```
 pub async fn compose_id_attribute<T>(
     api: &Factom,
     receiver_chain: &str,
@@ -119,7 +193,7 @@ where
     T: Serialize,
 {
     let mut req = ApiRequest::new("compose-identity-attribute");
-    let mut attr_list = vec![HashMap::new()];
+    let mut attr_list = Vec::new();
     req.params
         .insert("receiver-chainid".to_string(), json!(receiver_chain));
     req.params
@@ -218,6 +292,26 @@ pub async fn compose_id_attribute_endorsement(
 /// priority key can always just replace a lower priority key that was
 /// compromised or simply lost. For more information on key replacements, see the
 /// compose-identity-key-replacement section.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let ecpub = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+///   let name = vec!["Factom", "Test Identity"];
+///   let pubkeys = vec!["idpub2g25nPNZ2kf6KGTjthYdHT3nykDbwEUEPyGJ52fo55SHwtAvLA"];
+///   let composed = compose::compose_id_chain(&client, name, pubkeys, ecpub, false)
+///                     .await
+///                     .unwrap();
+///   let commit = &composed.result.commit.params.message;
+///   chain::commit_chain(&client, commit).await.unwrap();
+///   let reveal = &composed.result.reveal.params.entry;
+///   chain::reveal_chain(&client, reveal).await.unwrap();
+/// }
+/// ```
 pub async fn compose_id_chain(
     api: &Factom,
     name: Vec<&str>,
@@ -281,7 +375,7 @@ pub async fn compose_id_key_replacement(
 /// compose-identity-attribute
 /// compose-identity-attribute-endorsement
 /// compose-identity-chain
-/// compose-identity-key-replacementt
+/// compose-identity-key-replacement
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Compose {
     pub commit: Commit,
@@ -293,7 +387,7 @@ pub struct Compose {
 /// compose-identity-attribute
 /// compose-identity-attribute-endorsement
 /// compose-identity-chain
-/// compose-identity-key-replacementt
+/// compose-identity-key-replacement
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Commit {
     pub jsonrpc: String,
@@ -307,7 +401,7 @@ pub struct Commit {
 /// compose-identity-attribute
 /// compose-identity-attribute-endorsement
 /// compose-identity-chain
-/// compose-identity-key-replacementt
+/// compose-identity-key-replacement
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CommitParams {
     pub message: String,
@@ -318,7 +412,7 @@ pub struct CommitParams {
 /// compose-identity-attribute
 /// compose-identity-attribute-endorsement
 /// compose-identity-chain
-/// compose-identity-key-replacementt
+/// compose-identity-key-replacement
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Reveal {
     pub jsonrpc: String,
@@ -332,7 +426,7 @@ pub struct Reveal {
 /// compose-identity-attribute
 /// compose-identity-attribute-endorsement
 /// compose-identity-chain
-/// compose-identity-key-replacementt
+/// compose-identity-key-replacement
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RevealParams {
     pub entry: String,
@@ -352,3 +446,118 @@ pub struct ComposeTx {
 pub struct TxParams {
     pub transaction: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn composed(commit_method: &str, reveal_method: &str) -> String {
+        mock::result(json!({
+            "commit": {
+                "jsonrpc": "2.0",
+                "id": 0,
+                "params": {"message": "00aa"},
+                "method": commit_method
+            },
+            "reveal": {
+                "jsonrpc": "2.0",
+                "id": 0,
+                "params": {"entry": "00bb"},
+                "method": reveal_method
+            }
+        }))
+    }
+
+    #[test]
+    fn identity_chain_composed() {
+        let body = composed("commit-chain", "reveal-chain");
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let ecpub = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+        let idpub = "idpub2g25nPNZ2kf6KGTjthYdHT3nykDbwEUEPyGJ52fo55SHwtAvLA";
+        let query = compose_id_chain(&client, vec!["Factom", "Test"], vec![idpub], ecpub, false);
+        let composed = fetch(query).unwrap().result;
+        assert_eq!(composed.commit.method, "commit-chain");
+        assert_eq!(composed.commit.params.message, "00aa");
+        assert_eq!(composed.reveal.method, "reveal-chain");
+        assert_eq!(composed.reveal.params.entry, "00bb");
+        let (method, params) = mock::method(&server.requests()[0]);
+        assert_eq!(method, "compose-identity-chain");
+        assert_eq!(params["name"], json!(["Factom", "Test"]));
+        assert_eq!(params["pubkeys"], json!([idpub]));
+    }
+
+    #[test]
+    fn entry_fields_hex_encoded() {
+        let chain = composed("commit-chain", "reveal-chain");
+        let entry = composed("commit-entry", "reveal-entry");
+        let server = mock::serve(vec![&chain, &entry]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let ecpub = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+        let chainid = "a642a8674f46696cc47fdb6b65f9c87b2a19c5ea8123b3d2f0c13b6f33a9d5ef";
+        fetch(compose_chain(&client, vec!["a", "b"], "hi", ecpub)).unwrap();
+        fetch(compose_entry(&client, chainid, vec!["a"], "hi", ecpub)).unwrap();
+        let requests = server.requests();
+        let (method, params) = mock::method(&requests[0]);
+        assert_eq!(method, "compose-chain");
+        let firstentry = json!({"extids": ["61", "62"], "content": "6869"});
+        assert_eq!(params["chain"]["firstentry"], firstentry);
+        assert_eq!(params["ecpub"], ecpub);
+        let (method, params) = mock::method(&requests[1]);
+        assert_eq!(method, "compose-entry");
+        let expected = json!({"chainid": chainid, "extids": ["61"], "content": "6869"});
+        assert_eq!(params["entry"], expected);
+    }
+
+    #[test]
+    fn transaction_composed() {
+        let body = mock::result(json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "params": {"transaction": "0201"},
+            "method": "factoid-submit"
+        }));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let composed = fetch(compose_transaction(&client, "my-tx")).unwrap().result;
+        assert_eq!(composed.method, "factoid-submit");
+        assert_eq!(composed.params.transaction, "0201");
+        let (method, params) = mock::method(&server.requests()[0]);
+        assert_eq!(method, "compose-transaction");
+        assert_eq!(params["tx-name"], "my-tx");
+    }
+
+    #[test]
+    fn identity_attribute_and_endorsement_composed() {
+        let attribute = composed("commit-entry", "reveal-entry");
+        let endorsement = composed("commit-entry", "reveal-entry");
+        let server = mock::serve(vec![&attribute, &endorsement]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let chainid = "3b69dabe22c014af9a9bc9dfa7917ce4602a03579597ddf184d8de56702512ae";
+        let signer = "idpub2g25nPNZ2kf6KGTjthYdHT3nykDbwEUEPyGJ52fo55SHwtAvLA";
+        let ecpub = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+        let attributes = vec![("email", "info@factom.com"), ("worksAt", "Factom Inc.")];
+        let query = compose_id_attribute(
+            &client, chainid, chainid, attributes, signer, chainid, ecpub, false,
+        );
+        assert_eq!(fetch(query).unwrap().result.commit.method, "commit-entry");
+        let entry_hash = "716526c3279184bca11fc453fa9c2ab2f4488a03c821ee107664c9052f01d733";
+        let query = compose_id_attribute_endorsement(
+            &client, chainid, entry_hash, signer, chainid, ecpub, false,
+        );
+        assert_eq!(fetch(query).unwrap().result.reveal.method, "reveal-entry");
+        let requests = server.requests();
+        let (method, params) = mock::method(&requests[0]);
+        assert_eq!(method, "compose-identity-attribute");
+        let expected = json!([
+            {"key": "email", "value": "info@factom.com"},
+            {"key": "worksAt", "value": "Factom Inc."}
+        ]);
+        assert_eq!(params["attributes"], expected);
+        assert_eq!(params["signer-chainid"], chainid);
+        let (method, params) = mock::method(&requests[1]);
+        assert_eq!(method, "compose-identity-attribute-endorsement");
+        assert_eq!(params["entry-hash"], entry_hash);
+        assert_eq!(params["signerkey"], signer);
+    }
+}
@@ -21,3 +21,53 @@ pub const FCT_REGEX: &str = "^FA[123][1-9A-HJ-NP-Za-km-z]{49}";
 pub const EC_REGEX: &str = "^EC[123][1-9A-HJ-NP-Za-km-z]{49}";
 /// Null key merkle root
 pub const NULL_KEYMR: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// Admin, entry credit and factoid block chain ids listed in directory blocks
+pub const SYSTEM_CHAINS: [&str; 3] = [
+    "000000000000000000000000000000000000000000000000000000000000000a",
+    "000000000000000000000000000000000000000000000000000000000000000c",
+    "000000000000000000000000000000000000000000000000000000000000000f",
+];
+/// Number of blocks searched back when looking for a replaced identity key
+pub const ID_KEY_WINDOW: usize = 144;
+/// Factoid transaction version
+pub const FCT_TX_VERSION: u64 = 2;
+/// RCD type prefix for a single ed25519 signature
+pub const RCD_TYPE_1: u8 = 0x01;
+/// Maximum number of requests in flight for functions making many calls
+pub const MAX_CONCURRENT_REQUESTS: usize = 8;
+/// Api error code returned when a commit has already been made
+pub const REPEATED_COMMIT: i16 = -32011;
+/// Pause between committing and revealing an entry
+pub const REVEAL_DELAY_MS: u64 = 300;
+/// Entry payload bytes paid for by each entry credit
+pub const ENTRY_COST_BYTES: usize = 1024;
+/// Additional entry credits charged for the first entry of a new chain
+pub const CHAIN_CREATION_EC: u64 = 10;
+/// Factoid public address prefix bytes
+pub const FCT_PUB_PREFIX: [u8; 2] = [0x5f, 0xb1];
+/// Factoid secret address prefix bytes
+pub const FCT_SEC_PREFIX: [u8; 2] = [0x64, 0x78];
+/// Entry credit public address prefix bytes
+pub const EC_PUB_PREFIX: [u8; 2] = [0x59, 0x2a];
+/// Entry credit secret address prefix bytes
+pub const EC_SEC_PREFIX: [u8; 2] = [0x5d, 0xb6];
+/// Identity public key prefix bytes
+pub const ID_PUB_PREFIX: [u8; 5] = [0x03, 0x45, 0xef, 0x9d, 0xe0];
+/// Identity secret key prefix bytes
+pub const ID_SEC_PREFIX: [u8; 5] = [0x03, 0x45, 0xf3, 0xd0, 0xd6];
+/// Number of words in a Koinify crowdsale passphrase
+pub const KOINIFY_WORD_COUNT: usize = 12;
+/// Address checksum length in bytes
+pub const CHECKSUM_LENGTH: usize = 4;
+/// Default maximum size of a response body, 64 MB
+pub const MAX_RESPONSE_BYTES: usize = 64 * 1024 * 1024;
+/// Environment variable holding the factomd url
+pub const FACTOMD_URL_VAR: &str = "FACTOMD_URL";
+/// Environment variable holding the walletd url
+pub const WALLETD_URL_VAR: &str = "WALLETD_URL";
+/// Environment variable holding basic auth credentials as user:password
+pub const BASIC_AUTH_VAR: &str = "FACTOM_BASIC_AUTH";
+/// JSON-RPC error code returned when a daemon doesn't support a method
+pub const METHOD_NOT_FOUND: i16 = -32601;
+/// Walletd error messages meaning the wallet simply has no addresses
+pub const EMPTY_WALLET_ERRORS: [&str; 2] = ["no addresses", "wallet is empty"];
@@ -0,0 +1,158 @@
+//! Hostname resolution for the http connector.
+use hyper::client::connect::dns::{GaiResolver, Name};
+use hyper::service::Service;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::thread;
+use std::vec;
+
+/// Future returned when resolving a hostname
+pub type Resolving = Pin<Box<dyn Future<Output = io::Result<vec::IntoIter<IpAddr>>> + Send>>;
+
+/// A lookup waiting for a resolver thread, along with where to send the result
+type Lookup = (
+    String,
+    futures::channel::oneshot::Sender<io::Result<Vec<IpAddr>>>,
+);
+
+/// Resolves hostnames for the client's connector. By default hyper's resolver
+/// is used, which runs lookups on the runtime's blocking thread pool. A client
+/// built with Factom::with_dns_threads uses a fixed number of dedicated threads
+/// instead.
+#[derive(Clone)]
+pub enum DnsResolver {
+    Runtime(GaiResolver),
+    Threads(ThreadResolver),
+}
+
+impl DnsResolver {
+    /// Uses the given number of dedicated threads if provided, otherwise the
+    /// runtime's blocking thread pool
+    pub fn new(threads: Option<usize>) -> DnsResolver {
+        match threads {
+            Some(threads) => DnsResolver::Threads(ThreadResolver::new(threads)),
+            None => DnsResolver::Runtime(GaiResolver::new()),
+        }
+    }
+}
+
+impl fmt::Debug for DnsResolver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DnsResolver::Runtime(_) => write!(f, "DnsResolver::Runtime"),
+            DnsResolver::Threads(pool) => write!(f, "DnsResolver::Threads({})", pool.threads),
+        }
+    }
+}
+
+impl Service<Name> for DnsResolver {
+    type Response = vec::IntoIter<IpAddr>;
+    type Error = io::Error;
+    type Future = Resolving;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        match self {
+            DnsResolver::Runtime(resolver) => {
+                let lookup = resolver.call(name);
+                Box::pin(async move { Ok(lookup.await?.collect::<Vec<_>>().into_iter()) })
+            }
+            DnsResolver::Threads(pool) => pool.resolve(name.as_str()),
+        }
+    }
+}
+
+/// A fixed pool of threads making blocking lookups, the threads exit once every
+/// clone of the resolver has been dropped
+#[derive(Clone)]
+pub struct ThreadResolver {
+    lookups: Arc<Mutex<Sender<Lookup>>>,
+    threads: usize,
+}
+
+impl ThreadResolver {
+    /// Spawns the resolver threads. Panics if no threads are requested.
+    pub fn new(threads: usize) -> ThreadResolver {
+        assert!(threads > 0, "At least one dns thread is required");
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..threads {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name("factom-dns".to_string())
+                .spawn(move || resolve_lookups(&receiver))
+                .expect("Spawning dns thread");
+        }
+        ThreadResolver {
+            lookups: Arc::new(Mutex::new(sender)),
+            threads,
+        }
+    }
+
+    /// Returns the number of resolver threads
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    fn resolve(&self, host: &str) -> Resolving {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        let queued = self
+            .lookups
+            .lock()
+            .expect("Dns lookup queue")
+            .send((host.to_string(), sender));
+        Box::pin(async move {
+            queued.map_err(|_| io::Error::other("dns threads stopped"))?;
+            let addrs = receiver
+                .await
+                .map_err(|_| io::Error::other("dns lookup dropped"))??;
+            Ok(addrs.into_iter())
+        })
+    }
+}
+
+/// Runs lookups from the queue until every sender has been dropped
+fn resolve_lookups(lookups: &Mutex<Receiver<Lookup>>) {
+    loop {
+        let next = lookups.lock().expect("Dns lookup queue").recv();
+        let (host, result) = match next {
+            Ok(lookup) => lookup,
+            Err(_) => return,
+        };
+        let addrs = (host.as_str(), 0)
+            .to_socket_addrs()
+            .map(|addrs| addrs.map(|addr| addr.ip()).collect());
+        // The caller may have given up waiting, the result can then be dropped
+        let _ = result.send(addrs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn threads_resolve_localhost() {
+        let mut resolver = DnsResolver::new(Some(2));
+        let name: Name = "localhost".parse().unwrap();
+        let addrs: Vec<IpAddr> = fetch(resolver.call(name)).unwrap().collect();
+        assert!(!addrs.is_empty());
+        assert!(addrs.iter().all(IpAddr::is_loopback));
+    }
+
+    #[test]
+    fn unknown_host_errors() {
+        let pool = ThreadResolver::new(1);
+        assert!(fetch(pool.resolve("unknown.invalid")).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 //! For creating and querying entires.
 use super::*;
+/// Chain creation responses, commit_chain and reveal_chain live in the chain
+/// module
+pub use crate::chain::{CommitChain, RevealChain};
 
 /// Send an Entry Commit Message to factom to create a new Entry. The entry commit
 /// hex encoded string is documented here:
@@ -34,6 +37,45 @@ pub async fn entry(api: &Factom, hash: &str) -> Result<ApiResponse<Entry>> {
     parse(response).await
 }
 
+/// Returns the unix timestamp at which an entry was recorded. The entry block
+/// containing the entry is found with the transaction call and the timestamp
+/// is read from the block's entry list.
+///
+/// Returns a FetchError::Unconfirmed if the entry is not yet in an entry block
+/// or the entry block doesn't belong to the given chain.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let hash = "716526c3279184bca11fc453fa9c2ab2f4488a03c821ee107664c9052f01d733";
+///   let chainid = "a642a8674f46696cc47fdb6b65f9c87b2a19c5ea8123b3d2f0c13b6f33a9d5ef";
+///   let timestamp = entry::entry_timestamp(&client, hash, chainid).await.unwrap();
+///   dbg!(timestamp);
+/// }
+/// ```
+pub async fn entry_timestamp(api: &Factom, entryhash: &str, chainid: &str) -> Result<i64> {
+    let unconfirmed = || FetchError::Unconfirmed(entryhash.to_string());
+    let tx = tx::entry_transaction(api, entryhash).await?.api_result()?;
+    if tx.includedinentryblock.is_empty() {
+        return Err(unconfirmed().into());
+    }
+    let eblock = block::entry_block(api, &tx.includedinentryblock)
+        .await?
+        .api_result()?;
+    if eblock.header.chainid != chainid {
+        return Err(unconfirmed().into());
+    }
+    eblock
+        .entrylist
+        .iter()
+        .find(|e| e.entryhash == entryhash)
+        .map(|e| e.timestamp)
+        .ok_or_else(|| unconfirmed().into())
+}
+
 /// Retrieve an entry or transaction in raw format, the data is a hex encoded string.
 pub async fn raw_data(api: &Factom, hash: &str) -> Result<ApiResponse<RawData>> {
     let mut req = ApiRequest::new("raw-data");
@@ -76,6 +118,36 @@ pub struct Entry {
     pub extids: Vec<String>,
 }
 
+impl Entry {
+    /// Entry credits paid to commit this entry, not counting the chain creation
+    /// cost if it was the first entry. Fails if the content or an external id
+    /// isn't valid hex.
+    pub fn ec_cost(&self) -> Result<u64> {
+        let extids = self.extids.iter().map(hex::decode);
+        let extids = extids.collect::<std::result::Result<Vec<_>, _>>()?;
+        let content = hex::decode(&self.content)?;
+        Ok(entry_cost(&extids, &content))
+    }
+}
+
+/// Entry credit cost of an entry, one credit for each started kilobyte of
+/// payload. The payload is the content plus every external id along with its
+/// two byte length, an empty entry still costs one credit.
+///
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// assert_eq!(entry::entry_cost(&[b"id".to_vec()], b"hello"), 1);
+/// assert_eq!(entry::entry_cost(&[], &[0u8; 1025]), 2);
+/// ```
+pub fn entry_cost(extids: &[Vec<u8>], content: &[u8]) -> u64 {
+    let extid_bytes: usize = extids.iter().map(|id| id.len() + 2).sum();
+    let payload = extid_bytes + content.len();
+    let kilobytes = payload.div_ceil(ENTRY_COST_BYTES);
+    kilobytes.max(1) as u64
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CommitEntry {
     pub message: String,
@@ -83,13 +155,6 @@ pub struct CommitEntry {
     pub entryhash: String,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct CommitChain {
-    pub chainid: String,
-    pub content: String,
-    pub extids: Vec<String>,
-}
-
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RawData {
     pub data: String,
@@ -103,9 +168,61 @@ pub struct PendingEntry {
     pub status: String,
 }
 
+impl PendingEntry {
+    /// Returns true when only the commit has been seen, the chainid is unknown
+    /// until the entry is revealed so it is returned as null by factomd. Once
+    /// the reveal arrives the chainid is populated.
+    pub fn is_commit_only(&self) -> bool {
+        self.chainid.is_none()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RevealEntry {
     pub message: String,
     pub entryhash: String,
     pub chainid: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_entry_commit_only() {
+        let json = r#"{"entryhash":"abc","chainid":null,"status":"TransactionACK"}"#;
+        let pending: PendingEntry = serde_json::from_str(json).unwrap();
+        assert!(pending.is_commit_only());
+    }
+
+    #[test]
+    fn entry_cost_boundaries() {
+        assert_eq!(entry_cost(&[], &[]), 1);
+        assert_eq!(entry_cost(&[], &[0u8; 1024]), 1);
+        assert_eq!(entry_cost(&[vec![0u8; 1022]], &[]), 1);
+        assert_eq!(entry_cost(&[vec![0u8; 1022]], &[0u8]), 2);
+        assert_eq!(entry_cost(&[], &[0u8; 10240]), 10);
+    }
+
+    #[test]
+    fn entry_ec_cost_decodes_hex() {
+        let entry = Entry {
+            chainid: String::new(),
+            content: "00".repeat(1100),
+            extids: vec!["ff".to_string()],
+        };
+        assert_eq!(entry.ec_cost().unwrap(), 2);
+        let invalid = Entry {
+            content: "zz".to_string(),
+            ..entry
+        };
+        assert!(invalid.ec_cost().is_err());
+    }
+
+    #[test]
+    fn pending_entry_revealed() {
+        let json = r#"{"entryhash":"abc","chainid":"def","status":"TransactionACK"}"#;
+        let pending: PendingEntry = serde_json::from_str(json).unwrap();
+        assert!(!pending.is_commit_only());
+    }
+}
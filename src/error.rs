@@ -0,0 +1,106 @@
+//! Errors raised by the client itself rather than by the network or api.
+use crate::responses::ApiError;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// Maximum number of characters of a response body included in error messages
+const BODY_DISPLAY_LIMIT: usize = 512;
+
+/// Client side errors, these are boxed into the crate's Result type and can be
+/// recovered with `downcast_ref::<FetchError>()` if the exact cause is needed.
+#[derive(Debug)]
+pub enum FetchError {
+    /// A factoshi or entry credit amount was negative, this indicates a daemon
+    /// or serialization bug as amounts can never be below zero.
+    NegativeAmount(i64),
+    /// The hash has not yet been included in a directory block
+    Unconfirmed(String),
+    /// The host could not be parsed into a valid uri
+    InvalidUri(String),
+    /// An operation did not complete within the allotted time
+    Timeout,
+    /// A connection to the daemon could not be established within the
+    /// configured connect timeout
+    ConnectTimeout,
+    /// The response did not arrive within the configured read timeout
+    ReadTimeout,
+    /// An address failed to decode or its checksum didn't match
+    InvalidAddress(String),
+    /// A mnemonic had the wrong number of words, holds the number given. The
+    /// words themselves are left out as they are secret.
+    InvalidMnemonic(usize),
+    /// A working transaction with the same name already exists in the wallet
+    TransactionNameExists(String),
+    /// The response had no api error but was missing data it should contain,
+    /// usually meaning the body was for a different call or a proxy's response
+    UnexpectedShape(String),
+    /// The daemon doesn't support the named api method
+    Unsupported(String),
+    /// The response body exceeded the configured maximum number of bytes
+    ResponseTooLarge(usize),
+    /// Data returned by the api could not be interpreted
+    Malformed(String),
+    /// An api error returned part way through a function making several calls
+    Api(ApiError),
+    /// The response body could not be deserialized, the raw body is kept to help
+    /// diagnose schema mismatches
+    Decode {
+        error: serde_json::Error,
+        body: String,
+    },
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::NegativeAmount(amount) => {
+                write!(f, "negative amount returned: {}", amount)
+            }
+            FetchError::Unconfirmed(hash) => {
+                write!(f, "{} is not included in a directory block", hash)
+            }
+            FetchError::InvalidUri(host) => write!(f, "invalid uri: {}", host),
+            FetchError::Timeout => write!(f, "timed out"),
+            FetchError::ConnectTimeout => write!(f, "timed out connecting"),
+            FetchError::ReadTimeout => write!(f, "timed out waiting for a response"),
+            FetchError::InvalidAddress(address) => write!(f, "invalid address: {}", address),
+            FetchError::InvalidMnemonic(count) => {
+                write!(f, "invalid mnemonic of {} words", count)
+            }
+            FetchError::TransactionNameExists(name) => {
+                write!(f, "transaction name already exists: {}", name)
+            }
+            FetchError::UnexpectedShape(missing) => {
+                write!(f, "unexpected response shape, missing: {}", missing)
+            }
+            FetchError::Unsupported(method) => {
+                write!(f, "{} is not supported by this daemon", method)
+            }
+            FetchError::ResponseTooLarge(limit) => {
+                write!(f, "response body exceeded {} bytes", limit)
+            }
+            FetchError::Malformed(reason) => write!(f, "malformed data: {}", reason),
+            FetchError::Api(error) => {
+                write!(f, "api error {}: {}", error.code, error.message)
+            }
+            FetchError::Decode { error, body } => {
+                let shown: String = body.chars().take(BODY_DISPLAY_LIMIT).collect();
+                let ellipsis = if shown.len() < body.len() { "..." } else { "" };
+                write!(
+                    f,
+                    "decoding response: {}, body: {}{}",
+                    error, shown, ellipsis
+                )
+            }
+        }
+    }
+}
+
+impl Error for FetchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FetchError::Decode { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,9 @@
 //! General functions relating to factomd
 use super::*;
+use futures::stream::{self, Stream};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::Duration;
 
 /// The current-minute API call returns:
 ///
@@ -42,6 +46,57 @@ pub async fn current_minute(api: &Factom) -> Result<ApiResponse<CurrentMinute>>
     parse(response).await
 }
 
+/// Returns whether factomd thinks it has stalled, read from the stalldetected
+/// field of current-minute.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let stalled = factomd::is_stalled(&client).await.unwrap();
+///   dbg!(stalled);
+/// }
+/// ```
+pub async fn is_stalled(api: &Factom) -> Result<bool> {
+    let minute = current_minute(api).await?.api_result()?;
+    Ok(minute.stalldetected)
+}
+
+/// Polls current-minute every poll interval, yielding the initial stall state
+/// and then again whenever it changes. Errors are yielded as they occur and
+/// polling continues, the stream never ends on its own.
+/// # Example
+/// ```no_run
+/// use factom::*;
+/// use futures::stream::StreamExt;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let mut stalls = factomd::watch_stalls(&client, Duration::from_secs(30)).boxed_local();
+///   while let Some(stalled) = stalls.next().await {
+///     println!("Stalled: {:?}", stalled);
+///   }
+/// }
+/// ```
+pub fn watch_stalls<'a>(api: &'a Factom, poll: Duration) -> impl Stream<Item = Result<bool>> + 'a {
+    stream::unfold((None, true), move |(last, first)| async move {
+        if !first {
+            api.clock.sleep(poll).await;
+        }
+        loop {
+            match is_stalled(api).await {
+                Ok(stalled) if Some(stalled) == last => api.clock.sleep(poll).await,
+                Ok(stalled) => return Some((Ok(stalled), (Some(stalled), false))),
+                Err(e) => return Some((Err(e), (last, false))),
+            }
+        }
+    })
+}
+
 ///  * Retrieve basic system information along with a description of the node’s
 ///  * current perception of the network. This includes the node’s role, the current
 ///  * leader block height, block minute, syncing status, authority set, currently
@@ -126,11 +181,31 @@ pub async fn diagnostics(api: &Factom) -> Result<ApiResponse<Diagnostics>> {
     parse(response).await
 }
 
+/// Returns the role of the node, read from the role field of diagnostics.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let role = factomd::server_role(&client).await.unwrap();
+///   assert_eq!(role, factomd::ServerRole::Follower);
+/// }
+/// ```
+pub async fn server_role(api: &Factom) -> Result<ServerRole> {
+    let diagnostics = diagnostics(api).await?.api_result()?;
+    Ok(ServerRole::from(diagnostics.role.as_str()))
+}
+
 /// Returns the number of Factoshis (Factoids *10^-8) that purchase a single
 /// Entry Credit. The minimum factoid fees are also determined by this rate, along
 /// with how complex the factoid transaction is.
+/// Multiplying the rate by a number of entry credits gives the factoshis to
+/// pass to tx::add_ec_output. The example needs walletd with a transaction
+/// named "my-tx" already created.
 /// # Example
-/// ```
+/// ```no_run
 /// use factom::*;
 ///
 /// #[tokio::main]
@@ -140,6 +215,11 @@ pub async fn diagnostics(api: &Factom) -> Result<ApiResponse<Diagnostics>> {
 ///   let response = factomd::entry_credit_rate(&client).await.expect("Api Request");
 ///   dbg!(&response);
 ///   assert!(response.result.rate > 0);
+///   // Factoshis needed to buy 50 entry credits
+///   let factoshis = response.result.rate * 50;
+///   let ec_address = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+///   let output = tx::add_ec_output(&client, "my-tx", ec_address, factoshis).await;
+///   assert!(output.expect("Api Request").success());
 /// }
 /// ```
 pub async fn entry_credit_rate(api: &Factom) -> Result<ApiResponse<EcRate>> {
@@ -148,6 +228,47 @@ pub async fn entry_credit_rate(api: &Factom) -> Result<ApiResponse<EcRate>> {
     parse(response).await
 }
 
+/// Returns the protocol features known to factomd along with the height each
+/// activates at and whether it is currently active. Only factomd builds which
+/// expose the activations call support this, other daemons return a
+/// FetchError::Unsupported.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let status = factomd::activation_status(&client).await.expect("Api Request");
+///   for (name, activation) in status.iter() {
+///     println!("{}: {} active: {}", name, activation.height, activation.active);
+///   }
+/// }
+/// ```
+pub async fn activation_status(api: &Factom) -> Result<ActivationStatus> {
+    let req = ApiRequest::new("activations");
+    let response = factomd_call(api, req).await;
+    activations_result(parse(response).await?)
+}
+
+fn activations_result(response: ApiResponse<Activations>) -> Result<ActivationStatus> {
+    if response.error.code == METHOD_NOT_FOUND {
+        return Err(FetchError::Unsupported("activations".to_string()).into());
+    }
+    let activations = response.api_result()?.activations;
+    let status = activations
+        .into_iter()
+        .map(|a| {
+            let activation = Activation {
+                height: a.activationheight,
+                active: a.active,
+            };
+            (a.name, activation)
+        })
+        .collect();
+    Ok(status)
+}
+
 /// Returns various heights that allows you to view the state of the blockchain.
 /// The heights returned provide a lot of information regarding the state of factomd,
 /// but not all are needed by most applications. The heights also indicate the
@@ -169,6 +290,8 @@ pub async fn entry_credit_rate(api: &Factom) -> Result<ApiResponse<EcRate>> {
 ///
 /// A fully synced node should show the same number for all, (except between
 /// minute 0 and 1, when leaderheight will be 1 block ahead.)
+///
+/// Heights are signed as a node which hasn't started syncing reports -1.
 /// # Example
 /// ```
 /// use factom::*;
@@ -180,6 +303,8 @@ pub async fn entry_credit_rate(api: &Factom) -> Result<ApiResponse<EcRate>> {
 ///   let response = factomd::heights(&client).await.expect("Api Request");
 ///   dbg!(&response);
 ///   assert!(response.result.leaderheight > 0);
+///   let lag = response.result.leaderheight - response.result.directoryblockheight;
+///   println!("Blocks behind the leaders: {}", lag);
 /// }
 /// ```
 pub async fn heights(api: &Factom) -> Result<ApiResponse<Heights>> {
@@ -208,10 +333,19 @@ pub async fn properties(api: &Factom) -> Result<ApiResponse<Properties>> {
     parse(response).await
 }
 
-// Retrieve a receipt providing cryptographically verifiable proof that
-// information was recorded in the factom blockchain. A boolean parameter
-//  "includerawentry" can be used to request that raw entry data be returned
-//  at receipt.entry.raw in the JSON result.
+/// Alias of `properties` named for the daemon it calls, the wallet versions are
+/// returned by `walletd::wallet_properties` instead.
+pub async fn factomd_properties(api: &Factom) -> Result<ApiResponse<Properties>> {
+    properties(api).await
+}
+
+/// Retrieve a receipt providing cryptographically verifiable proof that
+/// information was recorded in the factom blockchain. A boolean parameter
+/// "includerawentry" can be used to request that raw entry data be returned
+/// at receipt.entry.raw in the JSON result.
+///
+/// The bitcoin anchor fields are None until the directory block has been
+/// anchored, which can take some time after the entry is recorded.
 /// # Example
 /// ```
 /// use factom::*;
@@ -242,8 +376,21 @@ pub async fn receipt(
 }
 
 /// Send a raw hex encoded binary message to the Factom network. This is mostly
-/// just for debugging and testing.
-pub async fn send_raw_message(api: &Factom, msg: &str) -> Result<ApiResponse<Receipt>> {
+/// just for debugging and testing. The message is checked to be valid hex before
+/// it is sent, returning a FetchError::Malformed otherwise.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let response = factomd::send_raw_message(&client, "0a01").await.unwrap();
+///   println!("{}", response.result.message);
+/// }
+/// ```
+pub async fn send_raw_message(api: &Factom, msg: &str) -> Result<ApiResponse<SendRawMessage>> {
+    utils::decode_hex(msg)?;
     let mut req = ApiRequest::new("send-raw-message");
     req.params.insert("message".to_string(), json!(msg));
     let response = factomd_call(api, req).await;
@@ -295,6 +442,50 @@ pub struct Diagnostics {
     pub elections: Elections,
 }
 
+/// activations function
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Activations {
+    pub activations: Vec<ActivationEntry>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivationEntry {
+    pub name: String,
+    pub activationheight: i64,
+    pub active: bool,
+}
+
+/// Feature name mapped to when it activates, returned by activation_status
+pub type ActivationStatus = HashMap<String, Activation>;
+
+/// Activation height of a protocol feature and whether it is active
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Activation {
+    pub height: i64,
+    pub active: bool,
+}
+
+/// Role a node plays in the network
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerRole {
+    Follower,
+    Leader,
+    Audit,
+    /// A role string not known to this client
+    Other(String),
+}
+
+impl From<&str> for ServerRole {
+    fn from(role: &str) -> Self {
+        match role {
+            "Follower" => ServerRole::Follower,
+            "Leader" => ServerRole::Leader,
+            "Audit" => ServerRole::Audit,
+            other => ServerRole::Other(other.to_string()),
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Syncing {
     pub status: String,
@@ -327,15 +518,24 @@ pub struct Audit {
     pub online: bool,
 }
 
+/// The election details are only present while an election is in progress
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Elections {
     pub inprogress: bool,
+    #[serde(default)]
+    pub vmindex: Option<i64>,
+    #[serde(default)]
+    pub fedindex: Option<i64>,
+    #[serde(default)]
+    pub fedid: Option<String>,
+    #[serde(default)]
+    pub round: Option<i64>,
 }
 
 // entry-credit-rate function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EcRate {
-    pub rate: i64,
+    pub rate: u64,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -352,11 +552,44 @@ pub struct Properties {
     pub factomdapiversion: String,
 }
 
+/// send-raw-message function
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SendRawMessage {
+    pub message: String,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Receipt {
     pub receipt: ReceiptInner,
 }
 
+impl Receipt {
+    /// Verifies the receipt offline by walking the merkle branch from the entry
+    /// hash up to the directory block keymr. Each node must contain the hash
+    /// from the level below as its left or right side, and its top must be the
+    /// sha256 of left and right concatenated. Returns a FetchError::Malformed if
+    /// a hash is not valid hex.
+    pub fn verify(&self) -> Result<bool> {
+        let receipt = &self.receipt;
+        let mut current = utils::decode_hex(&receipt.entry.entryhash)?;
+        for node in receipt.merklebranch.iter() {
+            let left = utils::decode_hex(&node.left)?;
+            let right = utils::decode_hex(&node.right)?;
+            let top = utils::decode_hex(&node.top)?;
+            if current != left && current != right {
+                return Ok(false);
+            }
+            let joined = [left, right].concat();
+            if Sha256::digest(&joined).as_slice() != top.as_slice() {
+                return Ok(false);
+            }
+            current = top;
+        }
+        let keymr = utils::decode_hex(&receipt.directoryblockkeymr)?;
+        Ok(current == keymr)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReceiptInner {
     pub entry: Entry,
@@ -364,6 +597,10 @@ pub struct ReceiptInner {
     pub entryblockkeymr: String,
     pub directoryblockkeymr: String,
     pub directoryblockheight: i64,
+    #[serde(default)]
+    pub bitcointransactionhash: Option<String>,
+    #[serde(default)]
+    pub bitcoinblockhash: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -384,12 +621,128 @@ pub struct Merklebranch {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::ManualClock;
+    use futures::stream::StreamExt;
+
+    fn node(left: &[u8], right: &[u8]) -> (Merklebranch, Vec<u8>) {
+        let top = Sha256::digest(&[left, right].concat()).to_vec();
+        let branch = Merklebranch {
+            left: hex::encode(left),
+            right: hex::encode(right),
+            top: hex::encode(&top),
+        };
+        (branch, top)
+    }
+
+    fn valid_receipt() -> Receipt {
+        let entryhash = [1u8; 32];
+        let (first, eblock) = node(&entryhash, &[2u8; 32]);
+        let (second, dblock) = node(&[3u8; 32], &eblock);
+        Receipt {
+            receipt: ReceiptInner {
+                entry: Entry {
+                    entryhash: hex::encode(entryhash),
+                    ..Default::default()
+                },
+                merklebranch: vec![first, second],
+                entryblockkeymr: hex::encode(&eblock),
+                directoryblockkeymr: hex::encode(&dblock),
+                directoryblockheight: 0,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn receipt_verifies() {
+        assert!(valid_receipt().verify().unwrap());
+    }
+
+    #[test]
+    fn tampered_receipt_fails() {
+        let mut receipt = valid_receipt();
+        receipt.receipt.merklebranch[0].right = hex::encode([4u8; 32]);
+        assert!(!receipt.verify().unwrap());
+        let mut receipt = valid_receipt();
+        receipt.receipt.directoryblockkeymr = hex::encode([5u8; 32]);
+        assert!(!receipt.verify().unwrap());
+    }
+
+    #[test]
+    fn receipt_anchor_optional() {
+        let mut value = serde_json::to_value(valid_receipt()).unwrap();
+        let inner = value["receipt"].as_object_mut().unwrap();
+        inner.remove("bitcointransactionhash");
+        inner.remove("bitcoinblockhash");
+        let unanchored: Receipt = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(unanchored.receipt.bitcoinblockhash, None);
+        value["receipt"]["bitcoinblockhash"] = json!("00ff");
+        let anchored: Receipt = serde_json::from_value(value).unwrap();
+        assert_eq!(anchored.receipt.bitcoinblockhash.as_deref(), Some("00ff"));
+    }
+
     #[test]
     fn current_minute_test() {
         let client = Factom::open_node();
         let query = current_minute(&client);
         let response = fetch(query).expect("Fetching query");
-        assert!(response.result.directoryblockheight > 1)
+        assert!(response.result.directoryblockheight > 1);
+        assert!((0..=10).contains(&response.result.minute));
+    }
+
+    fn minute_body(stalled: bool) -> String {
+        mock::result(json!({
+            "leaderheight": 1000,
+            "directoryblockheight": 999,
+            "minute": 4,
+            "currentblockstarttime": 1_590_000_000_000_000_000i64,
+            "currentminutestarttime": 1_590_000_240_000_000_000i64,
+            "currenttime": 1_590_000_250_000_000_000i64,
+            "directoryblockinseconds": 600,
+            "stalldetected": stalled,
+            "faulttimeout": 120,
+            "roundtimeout": 30
+        }))
+    }
+
+    #[test]
+    fn current_minute_parsed() {
+        let body = minute_body(false);
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let response = fetch(current_minute(&client)).unwrap();
+        assert_eq!(response.result.minute, 4);
+        assert_eq!(response.result.directoryblockinseconds, 600);
+        assert!(!response.result.stalldetected);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn stalled_and_healthy() {
+        let (healthy, stalled) = (minute_body(false), minute_body(true));
+        let server = mock::serve(vec![&healthy, &stalled]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        assert!(!fetch(is_stalled(&client)).unwrap());
+        assert!(fetch(is_stalled(&client)).unwrap());
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn watch_stalls_yields_changes() {
+        let bodies: Vec<String> = [false, false, true, false]
+            .iter()
+            .map(|stalled| minute_body(*stalled))
+            .collect();
+        let server = mock::serve(bodies.iter().map(String::as_str).collect());
+        let client =
+            Factom::custom_node(&server.url, &server.url).with_clock(Rc::new(ManualClock::new()));
+        let stalls = watch_stalls(&client, Duration::from_secs(30));
+        let states: Vec<bool> = fetch(stalls.take(3).collect::<Vec<_>>())
+            .into_iter()
+            .map(|stalled| stalled.unwrap())
+            .collect();
+        assert_eq!(states, vec![false, true, false]);
+        assert_eq!(server.requests().len(), 4);
     }
 
     #[test]
@@ -400,6 +753,105 @@ mod tests {
         assert!(response.result.leaderheight > 1)
     }
 
+    #[test]
+    fn diagnostics_during_election() {
+        let fedid = "8888882f5002ff95fce15d20ef9a5b0f23e5e9dd4ee6c85a4cc3de35e8b5e2d4";
+        let body = mock::result(json!({
+            "name": "FNode0",
+            "id": "38bab1455b7bd7e5efd15c53c777c79d0c988e9210f1da49a99d95b3a6417be9",
+            "publickey": "cc1985cdfae4e32b5a454dfda8ce5e1361558482684f3367649c3ad852c8e31a",
+            "role": "Audit",
+            "leaderheight": 1000,
+            "currentminute": 3,
+            "currentminuteduration": 12.5,
+            "previousminuteduration": 60.1,
+            "balancehash": "aa",
+            "tempbalancehash": "bb",
+            "lastblockfromdbstate": false,
+            "syncing": {"status": "Processing"},
+            "authset": {"leaders": [], "audits": []},
+            "elections": {
+                "inprogress": true,
+                "vmindex": 2,
+                "fedindex": 4,
+                "fedid": fedid,
+                "round": 1
+            }
+        }));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &mock::closed_port());
+        let diagnostics = fetch(diagnostics(&client)).unwrap().result;
+        assert_eq!(
+            ServerRole::from(diagnostics.role.as_str()),
+            ServerRole::Audit
+        );
+        assert_eq!(diagnostics.currentheight, 0);
+        assert!(diagnostics.syncing.missing.is_empty());
+        let elections = diagnostics.elections;
+        assert!(elections.inprogress);
+        assert_eq!(elections.fedid.as_deref(), Some(fedid));
+        assert_eq!((elections.vmindex, elections.round), (Some(2), Some(1)));
+        server.requests();
+    }
+
+    #[test]
+    fn raw_message_sent() {
+        let body = mock::result(json!({"message": "Successfully sent the message"}));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &mock::closed_port());
+        let response = fetch(send_raw_message(&client, "0a01ff")).unwrap();
+        assert_eq!(response.result.message, "Successfully sent the message");
+        let (method, params) = mock::method(&server.requests()[0]);
+        assert_eq!(method, "send-raw-message");
+        assert_eq!(params["message"], "0a01ff");
+    }
+
+    #[test]
+    fn raw_message_must_be_hex() {
+        let client = Factom::custom_node(&mock::closed_port(), &mock::closed_port());
+        let err = fetch(send_raw_message(&client, "not hex")).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FetchError>(),
+            Some(FetchError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn activations_parsed() {
+        let body = r#"{"jsonrpc":"2.0","id":0,"result":{"activations":[
+            {"name":"ELECTION_NO_SORT","activationheight":44000,"active":true},
+            {"name":"FUTURE_FEATURE","activationheight":999999,"active":false}]}}"#;
+        let response: ApiResponse<Activations> = serde_json::from_str(body).unwrap();
+        let status = activations_result(response).unwrap();
+        assert_eq!(status.len(), 2);
+        let sort = &status["ELECTION_NO_SORT"];
+        assert_eq!((sort.height, sort.active), (44000, true));
+        assert!(!status["FUTURE_FEATURE"].active);
+    }
+
+    #[test]
+    fn activations_unsupported() {
+        let body = r#"{"jsonrpc":"2.0","id":0,
+            "error":{"code":-32601,"message":"Method not found"}}"#;
+        let response: ApiResponse<Activations> = serde_json::from_str(body).unwrap();
+        let err = activations_result(response).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FetchError>(),
+            Some(FetchError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn server_role_strings() {
+        assert_eq!(ServerRole::from("Follower"), ServerRole::Follower);
+        assert_eq!(ServerRole::from("Leader"), ServerRole::Leader);
+        assert_eq!(ServerRole::from("Audit"), ServerRole::Audit);
+        assert_eq!(
+            ServerRole::from("Observer"),
+            ServerRole::Other("Observer".to_string())
+        );
+    }
+
     #[test]
     fn entry_credit_rate_test() {
         let client = Factom::open_node();
@@ -423,4 +875,14 @@ mod tests {
         let response = fetch(query).expect("Fetching query");
         assert!(response.result.factomdversion.len() > 1)
     }
+
+    #[test]
+    fn factomd_properties_routes_to_factomd() {
+        let body = json!({"factomdversion": "6.6.0", "factomdapiversion": "2.0"});
+        let server = mock::serve(vec![&mock::result(body)]);
+        let client = Factom::custom_node(&server.url, &mock::closed_port());
+        let response = fetch(factomd_properties(&client)).unwrap();
+        assert_eq!(response.result.factomdversion, "6.6.0");
+        assert_eq!(mock::method(&server.requests()[0]).0, "properties");
+    }
 }
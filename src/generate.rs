@@ -2,7 +2,8 @@
 use super::*;
 
 /// Create a new Entry Credit Address and store it in the wallet. If the wallet
-/// is encrypted, it must be unlocked prior to using this command.
+/// is encrypted, it must be unlocked prior to using this command. Each call
+/// adds a new address to the wallet, remove it if it's only needed briefly.
 /// # Example
 /// ```
 /// use factom::*;
@@ -24,7 +25,9 @@ pub async fn ec_address(api: &Factom) -> Result<ApiResponse<Generate>> {
     parse(response).await
 }
 
-/// Create a new Entry Credit Address and store it in the wallet.
+/// Create a new Factoid Address and store it in the wallet. As with
+/// `ec_address` this adds to the wallet and needs an unlocked wallet if it's
+/// encrypted.
 /// # Example
 /// ```
 /// use factom::*;
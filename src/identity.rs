@@ -51,7 +51,7 @@ pub async fn all_id_keys(api: &Factom) -> Result<ApiResponse<IdKeys>> {
 /// async fn main() {
 ///   let client = Factom::open_node();
 ///   let chainid = "3b69dabe22c014af9a9bc9dfa7917ce4602a03579597ddf184d8de56702512ae";
-///   let height = 163419;
+///   let height = Height(163419);
 ///   let response = identity::active_id_keys(&client, chainid, Some(height)).await.unwrap();
 ///   dbg!(&response);
 /// }
@@ -59,7 +59,7 @@ pub async fn all_id_keys(api: &Factom) -> Result<ApiResponse<IdKeys>> {
 pub async fn active_id_keys(
     api: &Factom,
     chain_id: &str,
-    height: Option<usize>,
+    height: Option<Height>,
 ) -> Result<ApiResponse<ActiveIdKeys>> {
     let mut req = ApiRequest::new("active-identity-keys");
     req.params.insert("chainid".to_string(), json!(chain_id));
@@ -71,6 +71,53 @@ pub async fn active_id_keys(
     parse(response).await
 }
 
+/// Finds the identity key that was replaced most recently before the given
+/// height. Active keys are fetched at the height and then at each preceding
+/// height, up to ID_KEY_WINDOW blocks back, until the key set differs. The key
+/// present in the earlier set but missing at the given height is returned.
+///
+/// Returns None if no key was replaced within the window. If the wallet is
+/// encrypted, it must be unlocked prior to using this command.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let chainid = "3b69dabe22c014af9a9bc9dfa7917ce4602a03579597ddf184d8de56702512ae";
+///   let height = Height(163421);
+///   let response = identity::previous_id_key(&client, chainid, height).await.unwrap();
+///   dbg!(&response);
+/// }
+/// ```
+pub async fn previous_id_key(
+    api: &Factom,
+    chain_id: &str,
+    height: Height,
+) -> Result<Option<String>> {
+    let current = fetch_active_keys(api, chain_id, height).await?;
+    let floor = height.0.saturating_sub(ID_KEY_WINDOW as u32);
+    for h in (floor..height.0).rev() {
+        let keys = fetch_active_keys(api, chain_id, Height(h)).await?;
+        if keys != current {
+            return Ok(replaced_key(&keys, &current));
+        }
+    }
+    Ok(None)
+}
+
+async fn fetch_active_keys(api: &Factom, chain_id: &str, height: Height) -> Result<Vec<String>> {
+    let response = active_id_keys(api, chain_id, Some(height)).await?;
+    Ok(response.api_result()?.keys)
+}
+
+/// Returns the first key of the older set that is no longer active
+fn replaced_key(older: &[String], current: &[String]) -> Option<String> {
+    older.iter().find(|key| !current.contains(key)).cloned()
+}
+
 ///  **Be careful using this function! Ensure that you have backups of important keys
 ///  before removing them.** Given an identity public key, this command deletes the
 ///  corresponding identity key pair from the wallet. Once executed, the user will
@@ -127,6 +174,30 @@ pub async fn id_key(api: &Factom, public: &str) -> Result<ApiResponse<Key>> {
     parse(response).await
 }
 
+/// Generates a new identity key pair and stores it in the wallet, returning the
+/// idpub public key and idsec secret. Walletd calls this generate-identity-key,
+/// this is the same call as `generate::identity_key` returning the identity
+/// module's Key. If the wallet is encrypted, it must be unlocked prior to using
+/// this command.
+///  # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let response = identity::new_identity_key(&client).await.unwrap();
+///   assert!(response.result.public.starts_with("idpub"));
+///   /// Remove key
+///   identity::remove_id_key(&client, &response.result.public).await.unwrap();
+/// }
+/// ```
+pub async fn new_identity_key(api: &Factom) -> Result<ApiResponse<Key>> {
+    let req = ApiRequest::new("generate-identity-key");
+    let response = walletd_call(api, req).await;
+    parse(response).await
+}
+
 /// all-identity-keys function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IdKeys {
@@ -153,3 +224,91 @@ pub struct ActiveIdKeys {
 pub struct RemoveIdKey {
     pub success: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_identity_key_generated() {
+        let body = mock::result(json!({
+            "public": "idpub2g25nPNZ2kf6KGTjthYdHT3nykDbwEUEPyGJ52fo55SHwtAvLA",
+            "secret": "idsec2rWrfNTD1x9HPPesA3fz8dmMNZdjmSBULHx8VTXE1J4D9icmAK"
+        }));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let response = fetch(new_identity_key(&client)).unwrap();
+        assert!(response.result.public.starts_with("idpub"));
+        assert!(response.result.secret.starts_with("idsec"));
+        assert_eq!(
+            mock::method(&server.requests()[0]).0,
+            "generate-identity-key"
+        );
+    }
+
+    /// Answers count active-identity-keys requests, idpub3 is replaced by
+    /// idpub4 from the given height onwards
+    fn keys_replaced_at(replaced_at: u64, count: usize) -> mock::MockServer {
+        mock::serve_with(count, move |request| {
+            let (_, params) = mock::method(request);
+            let height = params["height"].as_u64().unwrap();
+            let last = if height >= replaced_at {
+                "idpub4"
+            } else {
+                "idpub3"
+            };
+            mock::result(json!({
+                "chainid": params["chainid"],
+                "height": height,
+                "keys": ["idpub1", "idpub2", last]
+            }))
+        })
+    }
+
+    #[test]
+    fn previous_id_key_found() {
+        let server = keys_replaced_at(997, 5);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let query = previous_id_key(&client, "aa", Height(1000));
+        assert_eq!(fetch(query).unwrap(), Some("idpub3".to_string()));
+        let heights: Vec<u64> = server
+            .requests()
+            .iter()
+            .map(|request| mock::method(request).1["height"].as_u64().unwrap())
+            .collect();
+        assert_eq!(heights, vec![1000, 999, 998, 997, 996]);
+    }
+
+    #[test]
+    fn previous_id_key_outside_window() {
+        let server = keys_replaced_at(0, ID_KEY_WINDOW + 1);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let query = previous_id_key(&client, "aa", Height(1000));
+        assert_eq!(fetch(query).unwrap(), None);
+        let requests = server.requests();
+        assert_eq!(requests.len(), ID_KEY_WINDOW + 1);
+        let last = mock::method(&requests[ID_KEY_WINDOW]).1["height"].as_u64();
+        assert_eq!(last, Some(1000 - ID_KEY_WINDOW as u64));
+    }
+
+    #[test]
+    fn replaced_key_found() {
+        let older = vec![
+            "idpub1".to_string(),
+            "idpub2".to_string(),
+            "idpub3".to_string(),
+        ];
+        let current = vec![
+            "idpub1".to_string(),
+            "idpub2".to_string(),
+            "idpub4".to_string(),
+        ];
+        assert_eq!(replaced_key(&older, &current), Some("idpub3".to_string()));
+    }
+
+    #[test]
+    fn replaced_key_unchanged() {
+        let keys = vec!["idpub1".to_string(), "idpub2".to_string()];
+        assert_eq!(replaced_key(&keys, &keys), None);
+    }
+}
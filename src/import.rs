@@ -2,7 +2,9 @@
 use super::*;
 use std::collections::HashMap;
 
-/// Import Factoid and/or Entry Credit address secret keys into the wallet.
+/// Import Factoid and/or Entry Credit address secret keys into the wallet. Fs
+/// and Es secrets can be mixed in the same call, the resulting key pairs are
+/// returned in the order the secrets were given.
 /// # Example
 /// ```
 /// use factom::*;
@@ -37,6 +39,10 @@ pub async fn import_addresses(
 /// imported. If the wallet is encrypted, it must be unlocked prior to using this
 /// command.
 ///
+/// Each secret is checked to be a valid idsec key before calling walletd, a
+/// FetchError::InvalidAddress naming the position of the first bad key is
+/// returned otherwise. The key itself is left out of the error.
+///
 /// # Example
 /// ```
 /// use factom::*;
@@ -53,6 +59,12 @@ pub async fn import_addresses(
 /// }
 /// ```
 pub async fn import_identity_keys(api: &Factom, keys: Vec<&str>) -> Result<ApiResponse<Keys>> {
+    for (index, key) in keys.iter().enumerate() {
+        if address::decode_checked(key, &ID_SEC_PREFIX).is_err() {
+            let position = format!("identity secret key at index {}", index);
+            return Err(FetchError::InvalidAddress(position).into());
+        }
+    }
     let mut req = ApiRequest::new("import-identity-keys");
     let mut secrets: Vec<HashMap<&str, &str>> = Vec::new();
     for address in keys {
@@ -70,6 +82,9 @@ pub async fn import_identity_keys(api: &Factom, keys: Vec<&str>) -> Result<ApiRe
 /// will be different. If the wallet is encrypted, it must be unlocked prior to
 /// using this command.
 ///
+/// The phrase must contain exactly twelve words, otherwise a
+/// FetchError::InvalidMnemonic is returned without calling walletd.
+///
 /// # Example
 /// ```
 /// use factom::*;
@@ -86,6 +101,10 @@ pub async fn import_identity_keys(api: &Factom, keys: Vec<&str>) -> Result<ApiRe
 /// }
 /// ```
 pub async fn import_koinify(api: &Factom, phrase: &str) -> Result<ApiResponse<Address>> {
+    let count = phrase.split_whitespace().count();
+    if count != KOINIFY_WORD_COUNT {
+        return Err(FetchError::InvalidMnemonic(count).into());
+    }
     let mut req = ApiRequest::new("import-koinify");
     req.params.insert("words".to_string(), json!(phrase));
     let response = walletd_call(api, req).await;
@@ -115,3 +134,81 @@ pub struct Key {
     pub public: String,
     pub secret: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_mixed_secrets() {
+        let fct = (
+            "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q",
+            "Fs3E9gV6DXsYzf7Fqx1fVBQPQXV695eP3k5XbmHEZVRLkMdD9qCK",
+        );
+        let ec = (
+            "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK",
+            "Es3LS7zYa9DSzZuUC14HDpMinehmzz61JG1XFY62rX5pVDenH8Pk",
+        );
+        let body = mock::result(json!({"addresses": [
+            {"public": fct.0, "secret": fct.1},
+            {"public": ec.0, "secret": ec.1}
+        ]}));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let response = fetch(import_addresses(&client, vec![fct.1, ec.1])).unwrap();
+        let publics: Vec<&str> = response
+            .result
+            .addresses
+            .iter()
+            .map(|a| a.public.as_str())
+            .collect();
+        assert_eq!(publics, vec![fct.0, ec.0]);
+        let (method, params) = mock::method(&server.requests()[0]);
+        assert_eq!(method, "import-addresses");
+        let expected = json!({"addresses": [{"secret": fct.1}, {"secret": ec.1}]});
+        assert_eq!(params, expected);
+    }
+
+    #[test]
+    fn identity_keys_checked() {
+        let client = Factom::custom_node(&mock::closed_port(), &mock::closed_port());
+        let valid = "idsec2rWrfNTD1x9HPPesA3fz8dmMNZdjmSBULHx8VTXE1J4D9icmAK";
+        let public = "idpub2g25nPNZ2kf6KGTjthYdHT3nykDbwEUEPyGJ52fo55SHwtAvLA";
+        let query = import_identity_keys(&client, vec![valid, public]);
+        let err = fetch(query).unwrap_err();
+        match err.downcast_ref::<FetchError>() {
+            Some(FetchError::InvalidAddress(position)) => {
+                assert_eq!(position, "identity secret key at index 1")
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn koinify_word_count_checked() {
+        let client = Factom::custom_node(&mock::closed_port(), &mock::closed_port());
+        let phrase = "yellow yellow yellow yellow yellow yellow yellow yellow yellow yellow yellow";
+        let err = fetch(import_koinify(&client, phrase)).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FetchError>(),
+            Some(FetchError::InvalidMnemonic(11))
+        ));
+        assert!(!err.to_string().contains("yellow"));
+    }
+
+    #[test]
+    fn koinify_words_sent() {
+        let body = mock::result(json!({
+            "public": "FA3cih2o2tjEUsnnFR4jX1tQXPpSXFwsp3rhVp6odL5PNCHWvZV1",
+            "secret": "Fs1xcsC2rLqDBXm5NbBYSsXR6KdTDmw6kb6nExL3jnpZ3F4wrAjE"
+        }));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let phrase = ["yellow"; 12].join(" ");
+        let response = fetch(import_koinify(&client, &phrase)).unwrap();
+        assert!(response.result.public.starts_with("FA"));
+        let (method, params) = mock::method(&server.requests()[0]);
+        assert_eq!(method, "import-koinify");
+        assert_eq!(params["words"], phrase);
+    }
+}
@@ -15,25 +15,35 @@ pub mod api;
 pub mod balance;
 pub mod block;
 pub mod chain;
+pub mod clock;
 pub mod compose;
 pub mod constants;
 pub mod debug;
+pub mod dns;
 pub mod entry;
+pub mod error;
 pub mod factomd;
 pub mod generate;
 pub mod identity;
 pub mod import;
+#[cfg(test)]
+mod mock;
+pub mod pool;
 pub mod requests;
 pub mod responses;
+pub mod runtime;
 pub mod tx;
 pub mod utils;
 pub mod walletd;
 
 pub use api::Factom;
+pub use block::Height;
 pub use constants::*;
-#[cfg(feature = "default")]
+pub use error::FetchError;
+pub use pool::FactomPool;
+#[cfg(any(feature = "default", feature = "async-std-runtime"))]
 pub use requests::fetch;
-pub use requests::ApiRequest;
+pub use requests::{ApiRequest, JsonRpcId};
 pub use responses::ApiResponse;
 
 #[cfg(feature = "default")]
@@ -41,8 +51,7 @@ pub use tokio::prelude::*;
 #[cfg(feature = "default")]
 pub use tokio::runtime::Runtime;
 
-#[cfg(feature = "default")]
-use futures::prelude::*;
+use dns::DnsResolver;
 use hyper::{client::HttpConnector, Client};
 use hyper_tls::HttpsConnector;
 use requests::{debug_call, factomd_call, parse, walletd_call};
@@ -51,7 +60,7 @@ use serde_json::json;
 use std::rc::Rc;
 
 /// Reference counted Hyper client with custom https connector
-pub type HttpsClient = Rc<Client<HttpsConnector<HttpConnector>, hyper::Body>>;
+pub type HttpsClient = Rc<Client<HttpsConnector<HttpConnector<DnsResolver>>, hyper::Body>>;
 
 /// Async return type for API Calls
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
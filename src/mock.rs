@@ -0,0 +1,112 @@
+//! Minimal http server for unit tests, answering requests with canned json
+//! bodies so functions can be tested without a running daemon.
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+
+/// Server answering one connection per body, in order
+pub struct MockServer {
+    pub url: String,
+    handle: JoinHandle<Vec<String>>,
+}
+
+impl MockServer {
+    /// Waits for every response to be served, returning the request bodies
+    pub fn requests(self) -> Vec<String> {
+        self.handle.join().expect("Mock server thread")
+    }
+}
+
+/// Starts a server on a free local port which responds to each connection with
+/// the next body, closing the connection afterwards
+pub fn serve(bodies: Vec<&str>) -> MockServer {
+    let mut bodies: Vec<String> = bodies.into_iter().map(String::from).collect();
+    bodies.reverse();
+    let count = bodies.len();
+    serve_with(count, move |_| bodies.pop().expect("Response body"))
+}
+
+/// Starts a server which answers count requests, the handler is given each
+/// request body and returns the response body. Useful when requests are made
+/// concurrently and arrive in no particular order.
+pub fn serve_with<F>(count: usize, mut handler: F) -> MockServer
+where
+    F: FnMut(&str) -> String + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Binding mock server");
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for _ in 0..count {
+            let (mut stream, _) = listener.accept().expect("Accepting connection");
+            let request = read_request(&mut stream);
+            let body = handler(&request);
+            requests.push(request);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream
+                .write_all(response.as_bytes())
+                .expect("Writing response");
+        }
+        requests
+    });
+    MockServer { url, handle }
+}
+
+/// Returns the method and params of a json-rpc request body
+pub fn method(request: &str) -> (String, serde_json::Value) {
+    let value: serde_json::Value = serde_json::from_str(request).expect("Parsing request");
+    let method = value["method"].as_str().unwrap_or_default().to_string();
+    (method, value["params"].clone())
+}
+
+/// Wraps a result in a json-rpc response body
+pub fn result(result: serde_json::Value) -> String {
+    serde_json::json!({"jsonrpc": "2.0", "id": 0, "result": result}).to_string()
+}
+
+/// Returns a json-rpc error response body
+pub fn error(code: i16, message: &str) -> String {
+    let error = serde_json::json!({"code": code, "message": message});
+    serde_json::json!({"jsonrpc": "2.0", "id": 0, "error": error}).to_string()
+}
+
+/// Returns the url of a local port nothing is listening on
+pub fn closed_port() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Binding port");
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+/// Reads the headers and body of a request, returning the body
+fn read_request(stream: &mut TcpStream) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).expect("Reading request");
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if n == 0 {
+            return String::new();
+        }
+    };
+    let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+    let length = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|len| len.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while buf.len() < header_end + length {
+        let n = stream.read(&mut chunk).expect("Reading request body");
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    String::from_utf8_lossy(&buf[header_end..]).into_owned()
+}
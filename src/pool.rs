@@ -0,0 +1,125 @@
+//! Several factom clients used together for failover between nodes.
+use super::*;
+use std::future::Future;
+
+/// A set of Factom clients tried in order for reads. If a node cannot be
+/// reached the next one is tried, api errors are returned as normal since the
+/// node was reachable. Writes should go through the primary so that commits,
+/// reveals and transactions are only ever submitted to a single node.
+#[derive(Debug, Clone)]
+pub struct FactomPool {
+    nodes: Vec<Factom>,
+    primary: usize,
+}
+
+impl FactomPool {
+    /// Creates a pool from the given clients, the first is used as the primary.
+    /// Panics if no clients are provided.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let pool = FactomPool::new(vec![Factom::new(), Factom::open_node()]);
+    /// assert_eq!(pool.nodes().len(), 2);
+    /// ```
+    pub fn new(nodes: Vec<Factom>) -> FactomPool {
+        assert!(!nodes.is_empty(), "FactomPool requires at least one node");
+        FactomPool { nodes, primary: 0 }
+    }
+
+    /// Sets which node is used for writes by its index in the pool.
+    /// Panics if the index is out of range.
+    pub fn with_primary(mut self, index: usize) -> FactomPool {
+        assert!(index < self.nodes.len(), "Primary index out of range");
+        self.primary = index;
+        self
+    }
+
+    /// Returns the clients in the order they are tried
+    pub fn nodes(&self) -> &[Factom] {
+        &self.nodes
+    }
+
+    /// Returns the client writes should be made with
+    pub fn primary(&self) -> &Factom {
+        &self.nodes[self.primary]
+    }
+
+    /// Runs a read against each node in order, failing over to the next node
+    /// when a connection error occurs. The first success, or the last error if
+    /// no node could be reached, is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///   let pool = FactomPool::new(vec![Factom::new(), Factom::open_node()]);
+    ///   let response = pool.read(|node| factomd::heights(node)).await.unwrap();
+    ///   assert!(response.success());
+    /// }
+    /// ```
+    pub async fn read<'a, F, Fut, T>(&'a self, call: F) -> Result<T>
+    where
+        F: Fn(&'a Factom) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_err = None;
+        for node in self.nodes.iter() {
+            match call(node).await {
+                Err(err) if is_connection_error(&*err) => last_err = Some(err),
+                result => return result,
+            }
+        }
+        Err(last_err.expect("FactomPool contains at least one node"))
+    }
+}
+
+/// Whether the error came from the http client rather than the api or decoding
+fn is_connection_error(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    err.downcast_ref::<hyper::Error>().is_some()
+        || matches!(
+            err.downcast_ref::<FetchError>(),
+            Some(FetchError::Timeout)
+                | Some(FetchError::ConnectTimeout)
+                | Some(FetchError::ReadTimeout)
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fails_over_to_second_node() {
+        let server = mock::serve(vec![&mock::result(json!({
+            "directoryblockheight": 1,
+            "leaderheight": 1,
+            "entryblockheight": 1,
+            "entryheight": 1
+        }))]);
+        let down_host = mock::closed_port();
+        let down = Factom::custom_node(&down_host, &down_host);
+        let up = Factom::custom_node(&server.url, &server.url);
+        let down_uri = Rc::clone(&down.factomd_uri);
+        let pool = FactomPool::new(vec![down, up]);
+        let response = fetch(pool.read(factomd::heights)).unwrap();
+        assert_eq!(response.result.directoryblockheight, 1);
+        assert_eq!(mock::method(&server.requests()[0]).0, "heights");
+        assert_eq!(pool.primary().factomd_uri, down_uri);
+    }
+
+    #[test]
+    fn api_errors_are_not_retried() {
+        let pool = FactomPool::new(vec![Factom::new(), Factom::open_node()]);
+        let calls = std::cell::Cell::new(0);
+        let query = pool.read(|_| {
+            calls.set(calls.get() + 1);
+            async { Err::<(), _>(FetchError::Malformed("bad".to_string()).into()) }
+        });
+        assert!(fetch(query).is_err());
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -5,13 +5,17 @@ use std::error::Error;
 use std::fmt::{self, Debug, Display};
 
 /// JSON responses are deserialized into this struct
+///
+/// Malformed or proxied responses can contain both a result and an error, in
+/// which case a nonzero error code takes precedence and the result should be
+/// ignored. See `outcome` which applies this rule.
 #[derive(Deserialize, Debug, PartialEq, Default)]
 pub struct ApiResponse<T>
 where
     T: Default,
 {
     pub jsonrpc: String,
-    pub id: u32,
+    pub id: JsonRpcId,
     #[serde(default)]
     pub result: T,
     #[serde(default)]
@@ -27,12 +31,12 @@ pub struct ApiError {
 
 impl<T> Display for ApiResponse<T>
 where
-    T: Default + Debug + Display,
+    T: Default + Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "id: {}\nresult: {}\n error: {}",
+            "id: {}\nresult: {:?}\n error: {}",
             self.id, self.result, self.error
         )
     }
@@ -40,13 +44,15 @@ where
 
 impl Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self)
+        write!(f, "code {}: {}", self.code, self.message)
     }
 }
 
+impl Error for ApiError {}
+
 impl<T> Error for ApiResponse<T>
 where
-    T: Default + Debug + Display,
+    T: Default + Debug,
 {
     fn description(&self) -> &str {
         &self.error.message
@@ -69,4 +75,148 @@ where
     pub fn success(&self) -> bool {
         self.error.code == 0i16
     }
+
+    /// Takes the result, or the api error if one was returned, so a response
+    /// can be unwrapped with `?` into anything accepting a std::error::Error.
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    ///   let client = Factom::open_node();
+    ///   let heights = factomd::heights(&client).await.unwrap().into_result()?;
+    ///   assert!(heights.leaderheight > 0);
+    ///   Ok(())
+    /// }
+    /// ```
+    pub fn into_result(self) -> std::result::Result<T, ApiError> {
+        self.outcome()
+    }
+
+    /// Resolves the response into either its result or its error. When a body
+    /// contains both a nonzero error code wins, an error object with a zero code
+    /// is treated as absent and the result is returned.
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let body = r#"{"jsonrpc":"2.0","id":0,"result":{"rate":1000},
+    ///   "error":{"code":-32603,"message":"Internal error"}}"#;
+    /// let response: ApiResponse<factomd::EcRate> = serde_json::from_str(body).unwrap();
+    /// assert_eq!(response.outcome().unwrap_err().code, -32603);
+    /// ```
+    pub fn outcome(self) -> std::result::Result<T, ApiError> {
+        if self.is_err() {
+            return Err(self.error);
+        }
+        Ok(self.result)
+    }
+
+    /// Sanity checks a successful response, returning a FetchError::UnexpectedShape
+    /// naming the missing field if present returns false. Fields defaulted by
+    /// serde would otherwise make a mismatched body look like a valid result.
+    pub(crate) fn require<F>(self, field: &str, present: F) -> Result<Self>
+    where
+        F: FnOnce(&T) -> bool,
+    {
+        if self.success() && !present(&self.result) {
+            return Err(FetchError::UnexpectedShape(field.to_string()).into());
+        }
+        Ok(self)
+    }
+
+    /// Replaces an error caused by the wallet holding no addresses with an empty
+    /// default result, a fresh wallet isn't a failure for calls listing or
+    /// totalling its addresses.
+    pub(crate) fn or_empty_wallet(mut self) -> Self {
+        let message = self.error.message.to_lowercase();
+        if self.is_err() && EMPTY_WALLET_ERRORS.iter().any(|m| message.contains(m)) {
+            self.error = ApiError::default();
+            self.result = T::default();
+        }
+        self
+    }
+
+    /// Takes the result, converting an api error into a FetchError::Api for
+    /// functions which chain several calls together
+    pub(crate) fn api_result(self) -> Result<T> {
+        if self.is_err() {
+            return Err(FetchError::Api(self.error).into());
+        }
+        Ok(self.result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed_response() -> ApiResponse<factomd::Heights> {
+        ApiResponse {
+            jsonrpc: JSONRPC.to_string(),
+            id: JsonRpcId::Num(0),
+            result: Default::default(),
+            error: ApiError {
+                code: -32008,
+                message: "Lookup error".to_string(),
+            },
+        }
+    }
+
+    fn leader_height(response: ApiResponse<factomd::Heights>) -> Result<i64> {
+        let heights = response.into_result()?;
+        Ok(heights.leaderheight)
+    }
+
+    #[test]
+    fn api_error_display() {
+        let error = ApiError {
+            code: -32602,
+            message: "Invalid params".to_string(),
+        };
+        let shown = error.to_string();
+        assert!(shown.contains("-32602"));
+        assert!(shown.contains("Invalid params"));
+    }
+
+    #[test]
+    fn api_response_display() {
+        let mut response = failed_response();
+        response.result.leaderheight = 42;
+        let shown = response.to_string();
+        assert!(shown.contains("leaderheight: 42"));
+        assert!(shown.contains("code -32008: Lookup error"));
+    }
+
+    #[test]
+    fn into_result_with_question_mark() {
+        let err = leader_height(failed_response()).unwrap_err();
+        let api_error = err.downcast_ref::<ApiError>().expect("ApiError");
+        assert_eq!(api_error.code, -32008);
+        assert_eq!(err.to_string(), "code -32008: Lookup error");
+        let mut ok = failed_response();
+        ok.error = ApiError::default();
+        ok.result.leaderheight = 10;
+        assert_eq!(leader_height(ok).unwrap(), 10);
+    }
+
+    #[test]
+    fn outcome_error_wins() {
+        let result = r#""result":{"directoryblockheight":5,"leaderheight":5,
+            "entryblockheight":5,"entryheight":5}"#;
+        let body = format!(
+            r#"{{"jsonrpc":"2.0","id":0,{},"error":{{"code":-32603,"message":"Internal error"}}}}"#,
+            result
+        );
+        let response: ApiResponse<factomd::Heights> = serde_json::from_str(&body).unwrap();
+        assert!(!response.success());
+        assert_eq!(response.outcome().unwrap_err().code, -32603);
+        let body = format!(
+            r#"{{"jsonrpc":"2.0","id":0,{},"error":{{"code":0,"message":""}}}}"#,
+            result
+        );
+        let response: ApiResponse<factomd::Heights> = serde_json::from_str(&body).unwrap();
+        assert_eq!(response.outcome().unwrap().leaderheight, 5);
+    }
 }
@@ -0,0 +1,93 @@
+//! Runtime dependent parts of the client, the blocking fetch helper and
+//! timers, selected by cargo feature. Tokio is used by default, to use
+//! async-std instead disable the default features:
+//! ```toml
+//! [dependencies]
+//! factom = {version="^2", default-features=false, features=["async-std-runtime"]}
+//! ```
+use crate::clock::Sleep;
+use std::future::Future;
+use std::time::Duration;
+
+/// Operations which depend on the async runtime in use
+pub trait AsyncRuntime {
+    /// Runs a future to completion, blocking the current thread
+    fn block_on<F: Future>(future: F) -> F::Output;
+    /// Returns a future which completes once the duration has elapsed
+    fn sleep(duration: Duration) -> Sleep;
+}
+
+/// The tokio runtime, used by default
+#[cfg(feature = "default")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Tokio;
+
+#[cfg(feature = "default")]
+impl AsyncRuntime for Tokio {
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut rt = tokio::runtime::Runtime::new().expect("Initialising Runtime");
+        rt.block_on(future)
+    }
+
+    fn sleep(duration: Duration) -> Sleep {
+        Box::pin(tokio::time::delay_for(duration))
+    }
+}
+
+/// The async-std runtime, enabled with the async-std-runtime feature. Hyper's
+/// connection tasks are spawned onto async-std when tokio is disabled.
+#[cfg(feature = "async-std-runtime")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AsyncStd;
+
+#[cfg(feature = "async-std-runtime")]
+impl AsyncRuntime for AsyncStd {
+    fn block_on<F: Future>(future: F) -> F::Output {
+        async_std::task::block_on(future)
+    }
+
+    fn sleep(duration: Duration) -> Sleep {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+#[cfg(feature = "async-std-runtime")]
+impl<F> hyper::rt::Executor<F> for AsyncStd
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    fn execute(&self, future: F) {
+        async_std::task::spawn(future);
+    }
+}
+
+/// The runtime used by fetch and the system clock, tokio takes precedence if
+/// both features are enabled
+#[cfg(feature = "default")]
+pub type DefaultRuntime = Tokio;
+
+/// The runtime used by fetch and the system clock, tokio takes precedence if
+/// both features are enabled
+#[cfg(all(feature = "async-std-runtime", not(feature = "default")))]
+pub type DefaultRuntime = AsyncStd;
+
+#[cfg(all(test, feature = "async-std-runtime"))]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn call_on_async_std() {
+        let heights = serde_json::json!({"directoryblockheight": 10, "leaderheight": 10,
+            "entryblockheight": 10, "entryheight": 10});
+        let server = mock::serve(vec![&mock::result(heights)]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let response = AsyncStd::block_on(async {
+            AsyncStd::sleep(Duration::from_millis(1)).await;
+            factomd::heights(&client).await
+        });
+        assert_eq!(response.unwrap().result.leaderheight, 10);
+        server.requests();
+    }
+}
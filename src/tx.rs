@@ -1,6 +1,11 @@
 //! Functions relating to transactions
 use super::*;
-use std::collections::HashMap;
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+use futures::stream::{self, Stream, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::future::Future;
+use std::time::Duration;
 
 /// This api call is used to find the status of a transaction, whether it be a
 /// factoid, reveal entry, or commit entry. When using this, you must specify the
@@ -86,6 +91,44 @@ pub async fn ec_ack(
     parse(response).await
 }
 
+/// Checks both halves of an entry's commit and reveal with a single ack using
+/// the entry hash and chain id, returning the commit status followed by the
+/// reveal status. If factomd can't link the entry to its commit, for example
+/// because the reveal hasn't arrived yet, the commit is looked up separately by
+/// its transaction id.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let committxid = "4876ffeb8f95b72911b4a5115dc8a9fbb89d874db2263a75a9062f37bbbf1fa7";
+///   let entryhash = "e96cca381bf25f6dd4dfdf9f7009ff84ee6edaa3f47f9ccf06d2787482438f4b";
+///   let chainid = "f9164cd66af9d5773b4523a510b5eefb9a5e626480feeb6671ef2d17510ca300";
+///   let (commit, reveal) = tx::entry_status(&client, committxid, entryhash, chainid)
+///                             .await
+///                             .unwrap();
+///   assert_eq!(commit, tx::TxStatus::DBlockConfirmed);
+///   assert_eq!(reveal, tx::TxStatus::DBlockConfirmed);
+/// }
+/// ```
+pub async fn entry_status(
+    api: &Factom,
+    committxid: &str,
+    entryhash: &str,
+    chainid: &str,
+) -> Result<(TxStatus, TxStatus)> {
+    let ack = ec_ack(api, entryhash, chainid, None).await?.api_result()?;
+    let reveal = ack.reveal_status();
+    let mut commit = ack.commit_status();
+    if commit == TxStatus::Unknown || ack.committxid.is_empty() {
+        let commit_ack = ec_ack(api, committxid, "c", None).await?.api_result()?;
+        commit = commit_ack.commit_status();
+    }
+    Ok((commit, reveal))
+}
+
 /// See documentation for ec_ack
 pub async fn fct_ack(
     api: &Factom,
@@ -120,6 +163,85 @@ pub async fn factoid_submit(api: &Factom, transaction: &str) -> Result<ApiRespon
     parse(response).await
 }
 
+/// Submits a signed factoid transaction and waits for it to reach
+/// DBlockConfirmed, polling ack every poll interval. If it hasn't confirmed
+/// within the timeout the identical transaction hex is submitted once more and
+/// waited on again. The final status is returned, which may still be
+/// unconfirmed if neither attempt confirmed in time.
+///
+/// Only the same signed transaction is ever resubmitted, so this can't result
+/// in a double spend.
+/// # Example
+/// ```no_run
+/// use factom::*;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let composed = compose::compose_transaction(&client, "my-tx").await.unwrap();
+///   let tx_hex = composed.result.params.transaction;
+///   let poll = Duration::from_secs(10);
+///   let timeout = Duration::from_secs(600);
+///   let status = tx::resubmit_transaction(&client, &tx_hex, poll, timeout).await.unwrap();
+///   dbg!(status);
+/// }
+/// ```
+pub async fn resubmit_transaction(
+    api: &Factom,
+    tx_hex: &str,
+    poll: Duration,
+    timeout: Duration,
+) -> Result<TxStatus> {
+    submit_until_confirmed(
+        api,
+        poll,
+        timeout,
+        || async move { Ok(factoid_submit(api, tx_hex).await?.api_result()?.txid) },
+        |txid| async move { Ok(fct_ack(api, &txid, None).await?.api_result()?.tx_status()) },
+    )
+    .await
+}
+
+async fn submit_until_confirmed<S, SFut, A, AFut>(
+    api: &Factom,
+    poll: Duration,
+    timeout: Duration,
+    mut submit: S,
+    mut ack: A,
+) -> Result<TxStatus>
+where
+    S: FnMut() -> SFut,
+    SFut: Future<Output = Result<String>>,
+    A: FnMut(String) -> AFut,
+    AFut: Future<Output = Result<TxStatus>>,
+{
+    let mut txid = String::new();
+    for _ in 0..2 {
+        txid = submit().await?;
+        let confirmed = utils::poll_until(api, poll, timeout, || {
+            let status = ack(txid.clone());
+            async move {
+                let status = status.await?;
+                Ok(if status == TxStatus::DBlockConfirmed {
+                    Some(status)
+                } else {
+                    None
+                })
+            }
+        })
+        .await;
+        match confirmed {
+            Ok(status) => return Ok(status),
+            Err(err) => match err.downcast_ref::<FetchError>() {
+                Some(FetchError::Timeout) => continue,
+                _ => return Err(err),
+            },
+        }
+    }
+    ack(txid).await
+}
+
 /// Retrieve details of a factoid transaction using a transaction’s hash
 /// (or corresponding transaction id).
 ///
@@ -145,6 +267,10 @@ pub async fn factoid_submit(api: &Factom, transaction: &str) -> Result<ApiRespon
 /// * “includedintransactionblock”:“”
 /// * “includedindirectoryblock”:“”
 /// * “includedindirectoryblockheight”:-1
+///
+/// This is a factomd call, not to be confused with the walletd `transactions`
+/// call which has a different response. See also the `factomd_transaction`
+/// alias.
 /// # Example
 /// ```
 /// use factom::*;
@@ -161,6 +287,137 @@ pub async fn factoid_submit(api: &Factom, transaction: &str) -> Result<ApiRespon
 /// }
 /// ```
 pub async fn transaction(api: &Factom, hash: &str) -> Result<ApiResponse<Transaction>> {
+    let mut req = ApiRequest::new("transaction");
+    req.params.insert("hash".to_string(), json!(hash));
+    let response = factomd_call(api, req).await;
+    parse(response)
+        .await?
+        .require("factoidtransaction", |tx: &Transaction| {
+            let unconfirmed = tx.includedindirectoryblockheight < 0;
+            let is_entry = !tx.includedinentryblock.is_empty();
+            unconfirmed || is_entry || tx.factoidtransaction != Factoidtransaction::default()
+        })
+}
+
+/// Alias of `transaction` named for the daemon it calls, retrieves a factoid
+/// transaction or entry from factomd.
+pub async fn factomd_transaction(api: &Factom, hash: &str) -> Result<ApiResponse<Transaction>> {
+    transaction(api, hash).await
+}
+
+/// Returns how many directory blocks deep a transaction is, the current
+/// directory block height minus the height the transaction was included at. A
+/// transaction in the latest block has a depth of 0, None is returned if the
+/// transaction has not yet been included in a directory block.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let txid = "a740ac489821399eac070cf3ba681bc4cb78058a5fedee5e407762aa3d1de158";
+///   let depth = tx::confirmation_depth(&client, txid).await.unwrap();
+///   assert!(depth.unwrap() > 0);
+/// }
+/// ```
+pub async fn confirmation_depth(api: &Factom, txid: &str) -> Result<Option<u32>> {
+    let (tx, heights) = futures::join!(transaction(api, txid), factomd::heights(api));
+    let included = tx?.api_result()?.includedindirectoryblockheight;
+    let head = heights?.api_result()?.directoryblockheight;
+    if included < 0 {
+        return Ok(None);
+    }
+    Ok(Some((head - included as i64).max(0) as u32))
+}
+
+/// Fetches each transaction and sums the fees paid, calculated as the total
+/// inputs minus the total factoid and entry credit outputs. Transactions which
+/// fail to fetch are skipped and returned alongside their error in the total.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let txid = "a740ac489821399eac070cf3ba681bc4cb78058a5fedee5e407762aa3d1de158";
+///   let fees = tx::total_fees(&client, &[txid]).await;
+///   assert!(fees.failed.is_empty());
+///   dbg!(fees.total);
+/// }
+/// ```
+pub async fn total_fees(api: &Factom, txids: &[&str]) -> FeeTotal {
+    let fees = stream::iter(txids)
+        .map(|txid| async move {
+            let fee = match transaction(api, txid).await {
+                Ok(response) => response.api_result().map(|tx| tx.factoidtransaction.fee()),
+                Err(err) => Err(err),
+            };
+            (txid.to_string(), fee)
+        })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await;
+    sum_fees(fees)
+}
+
+fn sum_fees(fees: Vec<(String, Result<i64>)>) -> FeeTotal {
+    let mut total = FeeTotal::default();
+    for (txid, fee) in fees {
+        match fee {
+            Ok(fee) => total.total += fee,
+            Err(err) => total.failed.push((txid, err)),
+        }
+    }
+    total
+}
+
+/// Queries the transaction api with an entry hash, returning the entry shaped
+/// response with the entry block and directory block the entry is included in.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let hash = "716526c3279184bca11fc453fa9c2ab2f4488a03c821ee107664c9052f01d733";
+///   let response = tx::entry_transaction(&client, hash).await.unwrap();
+///   assert!(response.success());
+/// }
+/// ```
+pub async fn entry_transaction(
+    api: &Factom,
+    entryhash: &str,
+) -> Result<ApiResponse<EntryTransaction>> {
+    let mut req = ApiRequest::new("transaction");
+    req.params.insert("hash".to_string(), json!(entryhash));
+    let response = factomd_call(api, req).await;
+    parse(response).await
+}
+
+/// Queries the transaction api with either a factoid transaction id or an entry
+/// hash, the result is distinguished by the shape of the response.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let hash = "716526c3279184bca11fc453fa9c2ab2f4488a03c821ee107664c9052f01d733";
+///   let response = tx::transaction_or_entry(&client, hash).await.unwrap();
+///   match response.result {
+///     tx::TransactionOrEntry::Factoid(tx) => { dbg!(tx); }
+///     tx::TransactionOrEntry::Entry(entry) => { dbg!(entry); }
+///   }
+/// }
+/// ```
+pub async fn transaction_or_entry(
+    api: &Factom,
+    hash: &str,
+) -> Result<ApiResponse<TransactionOrEntry>> {
     let mut req = ApiRequest::new("transaction");
     req.params.insert("hash".to_string(), json!(hash));
     let response = factomd_call(api, req).await;
@@ -195,6 +452,36 @@ pub async fn pending_transactions(
     parse(response).await
 }
 
+/// Returns only the pending transactions that have reached DBlockConfirmed and
+/// can be treated as settled, optionally filtered by address.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let confirmed = tx::pending_transactions_confirmed(&client, None)
+///                             .await
+///                             .unwrap();
+///   dbg!(&confirmed);
+/// }
+/// ```
+pub async fn pending_transactions_confirmed(
+    api: &Factom,
+    address: Option<&str>,
+) -> Result<Vec<PendingTx>> {
+    let pending = pending_transactions(api, address).await?.api_result()?;
+    Ok(confirmed_only(pending))
+}
+
+fn confirmed_only(pending: Vec<PendingTx>) -> Vec<PendingTx> {
+    pending
+        .into_iter()
+        .filter(|tx| tx.tx_status() == TxStatus::DBlockConfirmed)
+        .collect()
+}
+
 /// When adding entry credit outputs, the amount given is in factoshis, not entry credits. This means math is required to determine the correct amount of factoshis to pay to get X EC.
 ///
 /// (ECRate * ECTotalOutput)
@@ -328,12 +615,44 @@ pub async fn delete_transaction(api: &Factom, tx_name: &str) -> Result<ApiRespon
 ///   assert!(response.success());
 /// }
 /// ```
+///
+/// The wallet's working transactions are checked first, if one with the same
+/// name already exists a FetchError::TransactionNameExists is returned.
 pub async fn new_transaction(api: &Factom, tx_name: &str) -> Result<ApiResponse<NewTx>> {
+    let existing = tmp_transactions(api).await?;
+    if existing.result.contains(tx_name) {
+        return Err(FetchError::TransactionNameExists(tx_name.to_string()).into());
+    }
+    let mut req = ApiRequest::new("new-transaction");
+    req.params.insert("tx-name".to_string(), json!(tx_name));
+    let response = walletd_call(api, req).await;
+    parse(response).await
+}
+
+/// Creates a new transaction with a name that is guaranteed not to collide with
+/// an existing working transaction. The name is the prefix followed by the
+/// first free numeric suffix, e.g. "payment-0", and is returned in the result.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let response = tx::new_transaction_unique(&client, "payment").await.unwrap();
+///   let tx_name = response.result.name;
+///   tx::delete_transaction(&client, &tx_name).await.unwrap();
+/// }
+/// ```
+pub async fn new_transaction_unique(api: &Factom, prefix: &str) -> Result<ApiResponse<NewTx>> {
+    let existing = tmp_transactions(api).await?;
+    let tx_name = existing.result.unique_name(prefix);
     let mut req = ApiRequest::new("new-transaction");
     req.params.insert("tx-name".to_string(), json!(tx_name));
     let response = walletd_call(api, req).await;
     parse(response).await
 }
+
 /// Signs the transaction. It is now ready to be executed.
 ///
 /// sign_transaction is used in the transaction process, the full process can be
@@ -399,7 +718,10 @@ pub async fn tmp_transactions(api: &Factom) -> Result<ApiResponse<TmpTransaction
 ///
 /// ### By Address
 /// Retrieves all transactions that involve a particular address.
-pub async fn transactions(api: &Factom, filter: SearchBy) -> Result<ApiResponse<Transactions>> {
+///
+/// This is a walletd call, the factomd `transaction` call is a separate method
+/// with a different response. See also the `wallet_transactions` alias.
+pub async fn transactions(api: &Factom, filter: SearchBy<'_>) -> Result<ApiResponse<Transactions>> {
     let mut req = ApiRequest::new("transactions");
     match filter {
         SearchBy::Txid(txid) => {
@@ -419,14 +741,116 @@ pub async fn transactions(api: &Factom, filter: SearchBy) -> Result<ApiResponse<
     parse(response).await
 }
 
-/// Search options for the transactions function
+/// Alias of `transactions` named for the daemon it calls, searches the
+/// transactions known to walletd.
+pub async fn wallet_transactions(
+    api: &Factom,
+    filter: SearchBy<'_>,
+) -> Result<ApiResponse<Transactions>> {
+    transactions(api, filter).await
+}
+
+/// Watches an address for new transactions, checking the directory block height
+/// every poll interval and querying walletd whenever a new block arrives.
+/// Transactions already involving the address when the stream starts are not
+/// yielded, each new transaction is yielded once as it confirms. Errors are
+/// yielded as they occur and polling continues, the stream never ends on its
+/// own.
+///
+/// Walletd must be synced for new transactions to show up, see
+/// walletd::wait_for_wallet_sync.
+/// # Example
+/// ```no_run
+/// use factom::*;
+/// use futures::stream::StreamExt;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let address = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+///   let poll = Duration::from_secs(60);
+///   let mut txs = tx::watch_address(&client, address, poll).boxed_local();
+///   while let Some(tx) = txs.next().await {
+///     println!("New transaction: {}", tx.expect("Watching address").txid);
+///   }
+/// }
+/// ```
+pub fn watch_address<'a>(
+    api: &'a Factom,
+    address: &'a str,
+    poll: Duration,
+) -> impl Stream<Item = Result<Txs>> + 'a {
+    let state = AddressWatch::default();
+    stream::unfold(state, move |mut state| async move {
+        let found = next_address_txs(api, address, poll, &mut state).await;
+        Some((found, state))
+    })
+    .flat_map(|found| {
+        let txs: Vec<Result<Txs>> = match found {
+            Ok(txs) => txs.into_iter().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        };
+        stream::iter(txs)
+    })
+}
+
+/// Progress of an address watch between polls
+#[derive(Default)]
+struct AddressWatch {
+    started: bool,
+    height: Option<i64>,
+    seen: HashSet<String>,
+}
+
+/// Polls until a new block brings transactions for the address that haven't
+/// been seen before. The first query only records the existing transactions.
+async fn next_address_txs(
+    api: &Factom,
+    address: &str,
+    poll: Duration,
+    state: &mut AddressWatch,
+) -> Result<Vec<Txs>> {
+    loop {
+        if state.started {
+            api.clock.sleep(poll).await;
+        }
+        state.started = true;
+        let height = factomd::heights(api)
+            .await?
+            .api_result()?
+            .directoryblockheight;
+        if state.height == Some(height) {
+            continue;
+        }
+        let mut req = ApiRequest::new("transactions");
+        req.params.insert("address".to_string(), json!(address));
+        let response = walletd_call(api, req).await;
+        let txs = parse::<Transactions, _>(response).await?.api_result()?;
+        let first = state.height.is_none();
+        state.height = Some(height);
+        let seen = &mut state.seen;
+        let new: Vec<Txs> = txs
+            .transactions
+            .into_iter()
+            .filter(|tx| seen.insert(tx.txid.clone()))
+            .collect();
+        if !first && !new.is_empty() {
+            return Ok(new);
+        }
+    }
+}
+
+/// Search options for the transactions function, txids and addresses are
+/// borrowed so they can come from runtime strings as well as literals
 /// * Range(usize, usize)
 /// * Txid(&str)
 /// * Address(&str)
-pub enum SearchBy {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchBy<'a> {
     Range(usize, usize),
-    Txid(&'static str),
-    Address(&'static str),
+    Txid(&'a str),
+    Address(&'a str),
 }
 
 /// factoid-submit function
@@ -441,22 +865,165 @@ pub struct FctSubmit {
 pub struct Transaction {
     #[serde(default)]
     pub factoidtransaction: Factoidtransaction,
+    #[serde(default)]
     pub includedintransactionblock: String,
+    #[serde(default)]
+    pub includedinentryblock: String,
     pub includedindirectoryblock: String,
     pub includedindirectoryblockheight: isize,
 }
 
+/// Sum of fees paid over several transactions, along with the transaction ids
+/// that could not be fetched and why
+#[derive(Debug, Default)]
+pub struct FeeTotal {
+    pub total: i64,
+    pub failed: Vec<(String, Box<dyn std::error::Error + Send + Sync>)>,
+}
+
+/// Response to the transaction api when queried with an entry hash
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryTransaction {
+    pub includedinentryblock: String,
+    pub includedindirectoryblock: String,
+    pub includedindirectoryblockheight: isize,
+}
+
+/// The transaction api returns differently shaped data depending on whether a
+/// factoid transaction id or an entry hash was queried
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TransactionOrEntry {
+    Factoid(Transaction),
+    Entry(EntryTransaction),
+}
+
+impl Default for TransactionOrEntry {
+    fn default() -> Self {
+        TransactionOrEntry::Factoid(Transaction::default())
+    }
+}
+
+impl<'de> Deserialize<'de> for TransactionOrEntry {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let parsed = if value.get("factoidtransaction").is_some() {
+            serde_json::from_value(value).map(TransactionOrEntry::Factoid)
+        } else if value.get("includedinentryblock").is_some() {
+            serde_json::from_value(value).map(TransactionOrEntry::Entry)
+        } else {
+            serde_json::from_value(value).map(TransactionOrEntry::Factoid)
+        };
+        parsed.map_err(serde::de::Error::custom)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Factoidtransaction {
     pub millitimestamp: usize,
     pub inputs: Option<Vec<Input>>,
     pub outputs: Option<Vec<Output>>,
-    pub outecs: Option<Vec<::serde_json::Value>>,
+    #[serde(default)]
+    pub outecs: Option<Vec<EcOutput>>,
     pub rcds: Vec<String>,
     pub sigblocks: Vec<Sigblock>,
     pub blockheight: usize,
 }
 
+impl Factoidtransaction {
+    /// Returns the fee paid, the total inputs minus the total factoid and entry
+    /// credit outputs
+    pub fn fee(&self) -> i64 {
+        fn sum<I: Iterator<Item = usize>>(amounts: I) -> i64 {
+            amounts.map(|amount| amount as i64).sum()
+        }
+        let inputs = sum(self.inputs.iter().flatten().map(|i| i.amount));
+        let outputs = sum(self.outputs.iter().flatten().map(|o| o.amount));
+        let outecs = sum(self.outecs.iter().flatten().map(|o| o.amount));
+        inputs - outputs - outecs
+    }
+
+    /// Verifies the signatures of the transaction offline. Each input is paired
+    /// positionally with an RCD and a signature block, the RCD must hash to the
+    /// input address and its public key must have signed the transaction ledger
+    /// data.
+    ///
+    /// Returns Ok(false) if any pairing or signature is invalid and a
+    /// FetchError::Malformed if the returned hex data cannot be decoded.
+    pub fn verify_signatures(&self) -> Result<bool> {
+        let inputs = self.inputs.as_deref().unwrap_or(&[]);
+        if inputs.len() != self.rcds.len() || inputs.len() != self.sigblocks.len() {
+            return Ok(false);
+        }
+        let ledger = self.marshal_ledger()?;
+        let pairs = inputs.iter().zip(&self.rcds).zip(&self.sigblocks);
+        for ((input, rcd), sigblock) in pairs {
+            let rcd = utils::decode_hex(rcd)?;
+            if rcd.len() != 33 || rcd[0] != RCD_TYPE_1 {
+                return Err(FetchError::Malformed(format!("unsupported rcd: {:x?}", rcd)).into());
+            }
+            if hex::encode(utils::double_sha(&rcd)) != input.address.to_lowercase() {
+                return Ok(false);
+            }
+            let signature = match sigblock.signatures.first() {
+                Some(signature) => utils::decode_hex(signature)?,
+                None => return Ok(false),
+            };
+            let pubkey = PublicKey::from_bytes(&rcd[1..])
+                .map_err(|e| FetchError::Malformed(e.to_string()))?;
+            let signature = Signature::try_from(&signature[..])
+                .map_err(|e| FetchError::Malformed(e.to_string()))?;
+            if pubkey.verify(&ledger, &signature).is_err() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Marshals the signed portion of the transaction: version, timestamp,
+    /// input/output counts followed by the inputs, outputs and ec outputs.
+    fn marshal_ledger(&self) -> Result<Vec<u8>> {
+        let inputs = self.inputs.as_deref().unwrap_or(&[]);
+        let outputs = self.outputs.as_deref().unwrap_or(&[]);
+        let outecs = self.outecs.as_deref().unwrap_or(&[]);
+        let mut data = Vec::new();
+        encode_varint(FCT_TX_VERSION, &mut data);
+        data.extend_from_slice(&(self.millitimestamp as u64).to_be_bytes()[2..]);
+        data.push(inputs.len() as u8);
+        data.push(outputs.len() as u8);
+        data.push(outecs.len() as u8);
+        for input in inputs {
+            encode_varint(input.amount as u64, &mut data);
+            data.extend(utils::decode_hex(&input.address)?);
+        }
+        for output in outputs {
+            encode_varint(output.amount as u64, &mut data);
+            data.extend(utils::decode_hex(&output.address)?);
+        }
+        for outec in outecs {
+            encode_varint(outec.amount as u64, &mut data);
+            data.extend(utils::decode_hex(&outec.address)?);
+        }
+        Ok(data)
+    }
+}
+
+/// Factom variable length integer encoding, big endian 7 bit groups with the
+/// high bit set on every byte except the last.
+fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    let mut bytes = vec![(value & 0x7f) as u8];
+    value >>= 7;
+    while value > 0 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.reverse();
+    out.extend(bytes);
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Input {
     pub amount: usize,
@@ -495,10 +1062,45 @@ pub struct PendingTx {
     pub status: String,
     pub inputs: Option<Vec<Input>>,
     pub outputs: Option<Vec<Output>>,
+    #[serde(default)]
     pub ecoutputs: Option<Vec<EcOutput>>,
     pub fees: usize,
 }
 
+impl PendingTx {
+    /// Parses the status string into a TxStatus
+    pub fn tx_status(&self) -> TxStatus {
+        TxStatus::from(self.status.as_str())
+    }
+}
+
+/// Status of a transaction as reported by factomd
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxStatus {
+    /// Not found anywhere
+    Unknown,
+    /// Found on local node, but not in network (Holding Map)
+    NotConfirmed,
+    /// Found in network, but not written to the blockchain yet (ProcessList)
+    TransactionAck,
+    /// Found in Blockchain
+    DBlockConfirmed,
+    /// A status string not known to this client
+    Other(String),
+}
+
+impl From<&str> for TxStatus {
+    fn from(status: &str) -> Self {
+        match status {
+            "Unknown" => TxStatus::Unknown,
+            "NotConfirmed" => TxStatus::NotConfirmed,
+            "TransactionACK" => TxStatus::TransactionAck,
+            "DBlockConfirmed" => TxStatus::DBlockConfirmed,
+            other => TxStatus::Other(other.to_string()),
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EntryAck {
     pub committxid: String,
@@ -509,12 +1111,31 @@ pub struct EntryAck {
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FactoidAck {
-    txid: String,
-    transactiondate: i64,
-    transactiondatestring: String,
-    blockdate: i64,
-    blockdatestring: String,
-    status: String,
+    pub txid: String,
+    pub transactiondate: i64,
+    pub transactiondatestring: String,
+    pub blockdate: i64,
+    pub blockdatestring: String,
+    pub status: String,
+}
+
+impl EntryAck {
+    /// Parses the commit section's status into a TxStatus
+    pub fn commit_status(&self) -> TxStatus {
+        TxStatus::from(self.commitdata.status.as_str())
+    }
+
+    /// Parses the reveal section's status into a TxStatus
+    pub fn reveal_status(&self) -> TxStatus {
+        TxStatus::from(self.entrydata.status.as_str())
+    }
+}
+
+impl FactoidAck {
+    /// Parses the status string into a TxStatus
+    pub fn tx_status(&self) -> TxStatus {
+        TxStatus::from(self.status.as_str())
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -532,6 +1153,7 @@ pub struct Entrydata {
 pub struct NewTx {
     #[serde(default)]
     pub feesrequired: usize,
+    #[serde(deserialize_with = "utils::bool_or_int")]
     pub signed: bool,
     pub name: String,
     pub timestamp: i64,
@@ -550,23 +1172,45 @@ pub struct TxInput {
     pub amount: i64,
 }
 
+impl TxInput {
+    /// Returns the amount as a u64, erroring if the daemon returned a negative value
+    pub fn amount_u64(&self) -> Result<u64> {
+        utils::non_negative(self.amount)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TxOutput {
     pub address: String,
     pub amount: i64,
 }
 
+impl TxOutput {
+    /// Returns the amount as a u64, erroring if the daemon returned a negative value
+    pub fn amount_u64(&self) -> Result<u64> {
+        utils::non_negative(self.amount)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ecoutput {
     pub address: String,
     pub amount: i64,
 }
 
+impl Ecoutput {
+    /// Returns the amount as a u64, erroring if the daemon returned a negative value
+    pub fn amount_u64(&self) -> Result<u64> {
+        utils::non_negative(self.amount)
+    }
+}
+
 /// add-input, add-output, add-fee, sub-fee, sign-transaction functions
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Tx {
     pub feespaid: i64,
     pub feesrequired: i64,
+    #[serde(deserialize_with = "utils::bool_or_int")]
     pub signed: bool,
     pub name: String,
     pub timestamp: i64,
@@ -582,6 +1226,7 @@ pub struct Tx {
 /// delete-transaction function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DeleteTx {
+    #[serde(deserialize_with = "utils::bool_or_int")]
     pub signed: bool,
     pub name: String,
     pub timestamp: i64,
@@ -599,6 +1244,24 @@ pub struct TmpTransactions {
     pub transactions: Option<Vec<TmpTransaction>>,
 }
 
+impl TmpTransactions {
+    /// Returns whether a working transaction with the given name exists
+    pub fn contains(&self, tx_name: &str) -> bool {
+        match &self.transactions {
+            Some(txs) => txs.iter().any(|tx| tx.tx_name == tx_name),
+            None => false,
+        }
+    }
+
+    /// Returns the prefix with the first numeric suffix not already in use
+    pub fn unique_name(&self, prefix: &str) -> String {
+        (0..)
+            .map(|i| format!("{}-{}", prefix, i))
+            .find(|name| !self.contains(name))
+            .expect("Finding free transaction name")
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TmpTransaction {
     #[serde(rename = "tx-name")]
@@ -615,11 +1278,120 @@ pub struct Transactions {
     pub transactions: Vec<Txs>,
 }
 
+impl Transactions {
+    /// Tags the transactions with the search mode used to fetch them, see
+    /// TransactionsResult for which fields each mode reliably populates.
+    pub fn by_mode(self, filter: &SearchBy) -> TransactionsResult {
+        match filter {
+            SearchBy::Range(..) => TransactionsResult::Range(self.transactions),
+            SearchBy::Txid(..) => TransactionsResult::Txid(self.transactions),
+            SearchBy::Address(..) => TransactionsResult::Address(self.transactions),
+        }
+    }
+
+    /// Renders the transactions as CSV with a header row, one row per
+    /// transaction: txid, block height, timestamp, total inputs, total outputs
+    /// and fees paid. Amounts are in factoshis.
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let txs = tx::Transactions { transactions: Vec::new() };
+    /// assert_eq!(txs.to_csv(), "txid,blockheight,timestamp,totalinputs,totaloutputs,feespaid\n");
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut out = Vec::new();
+        self.write_csv(&mut out).expect("Writing csv to memory");
+        String::from_utf8(out).expect("Csv is valid utf8")
+    }
+
+    /// Streaming version of to_csv, writes the rows to any writer
+    pub fn write_csv<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writeln!(
+            writer,
+            "txid,blockheight,timestamp,totalinputs,totaloutputs,feespaid"
+        )?;
+        for tx in self.transactions.iter() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                tx.txid, tx.blockheight, tx.timestamp, tx.totalinputs, tx.totaloutputs, tx.feespaid
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Totals the transactions, typically those returned by a SearchBy::Range
+    /// query. The outputs list isn't typed so the totals reported by walletd
+    /// for each transaction are used rather than summing the list. Amounts are
+    /// in factoshis.
+    /// # Example
+    /// ```
+    /// use factom::*;
+    ///
+    /// let summary = tx::Transactions::default().summary();
+    /// assert_eq!(summary.count, 0);
+    /// assert_eq!(summary.total_fees, 0);
+    /// ```
+    pub fn summary(&self) -> RangeSummary {
+        let mut summary = RangeSummary::default();
+        for tx in self.transactions.iter() {
+            summary.count += 1;
+            summary.total_inputs += tx.totalinputs;
+            summary.total_outputs += tx.totaloutputs;
+            summary.total_fees += tx.feespaid;
+        }
+        summary
+    }
+}
+
+/// Aggregate totals over a set of transactions, see Transactions::summary
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct RangeSummary {
+    pub count: usize,
+    pub total_inputs: i64,
+    pub total_outputs: i64,
+    pub total_fees: i64,
+}
+
+/// Results of the transactions function by search mode
+/// * Range and Address searches populate every field including the block height
+/// * Txid searches don't report a block height, it is always returned as 0
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionsResult {
+    Range(Vec<Txs>),
+    Txid(Vec<Txs>),
+    Address(Vec<Txs>),
+}
+
+impl TransactionsResult {
+    /// Returns the transactions regardless of search mode
+    pub fn transactions(&self) -> &[Txs] {
+        match self {
+            TransactionsResult::Range(txs)
+            | TransactionsResult::Txid(txs)
+            | TransactionsResult::Address(txs) => txs,
+        }
+    }
+
+    /// Returns the block height of each transaction, None for Txid searches
+    /// where the height isn't reported
+    pub fn blockheights(&self) -> Vec<Option<i64>> {
+        match self {
+            TransactionsResult::Txid(txs) => vec![None; txs.len()],
+            TransactionsResult::Range(txs) | TransactionsResult::Address(txs) => {
+                txs.iter().map(|tx| Some(tx.blockheight)).collect()
+            }
+        }
+    }
+}
+
 /// Individual transactions from the transactions function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Txs {
     pub blockheight: i64,
     pub feespaid: i64,
+    #[serde(deserialize_with = "utils::bool_or_int")]
     pub signed: bool,
     pub timestamp: i64,
     pub totalecoutputs: i64,
@@ -630,3 +1402,536 @@ pub struct Txs {
     pub ecoutputs: Vec<Ecoutput>,
     pub txid: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use ed25519_dalek::{Keypair, SecretKey, Signer};
+    use std::cell::Cell;
+
+    fn signed_transaction() -> Factoidtransaction {
+        let secret = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+        let public: PublicKey = (&secret).into();
+        let keypair = Keypair { secret, public };
+        let mut rcd = vec![RCD_TYPE_1];
+        rcd.extend_from_slice(public.as_bytes());
+        let mut tx = Factoidtransaction {
+            millitimestamp: 1575574473164,
+            inputs: Some(vec![Input {
+                amount: 1_000_012_000,
+                address: hex::encode(utils::double_sha(&rcd)),
+                useraddress: String::new(),
+            }]),
+            outputs: Some(vec![Output {
+                amount: 1_000_000_000,
+                address: hex::encode([3u8; 32]),
+                useraddress: String::new(),
+            }]),
+            outecs: None,
+            rcds: vec![hex::encode(&rcd)],
+            sigblocks: Vec::new(),
+            blockheight: 0,
+        };
+        let signature = keypair.sign(&tx.marshal_ledger().unwrap());
+        tx.sigblocks = vec![Sigblock {
+            signatures: vec![hex::encode(signature.to_bytes().as_ref())],
+        }];
+        tx
+    }
+
+    #[test]
+    fn verify_valid_signatures() {
+        let tx = signed_transaction();
+        assert!(tx.verify_signatures().unwrap());
+    }
+
+    #[test]
+    fn verify_corrupted_signature() {
+        let mut tx = signed_transaction();
+        let mut signature = hex::decode(&tx.sigblocks[0].signatures[0]).unwrap();
+        signature[0] ^= 0xff;
+        tx.sigblocks[0].signatures[0] = hex::encode(signature);
+        assert!(!tx.verify_signatures().unwrap());
+    }
+
+    // A transaction spending from the public sandbox address FA2jK2HcLnRd...,
+    // its ledger bytes laid out by hand following factomd's signed data format
+    // and signed by an independent ed25519 implementation. Marshalling and
+    // verification are checked against the wire format rather than themselves.
+    const WIRE_LEDGER: &str = concat!(
+        "02016ed78e89cc010101",
+        "83e1ce9e60646f3e8750c550e4582eca5047546ffef89c13a175985e320232bacac81cc428",
+        "83dceb9400d929b10b488382de5cccaa2a47b2a77ac04fd78926c67eb264615fc388063ab9",
+        "84e2ad004d8fd6215f6cc1245908939c7e6cae1c1189a12fdfe32309c9cf4cee0729c602"
+    );
+    const WIRE_SIGNATURE: &str = concat!(
+        "9cc319e3c0f0fe038eaf784c73e8e4ecc8f831b3198f6574f8f2058045f77bfc",
+        "0918f174007601adbb7af1eecdd5943b2f3062c0032fc502e5fd1d49d7af210e"
+    );
+
+    fn wire_transaction() -> Factoidtransaction {
+        Factoidtransaction {
+            millitimestamp: 1575574473164,
+            inputs: Some(vec![Input {
+                amount: 1_010_012_000,
+                address: "646f3e8750c550e4582eca5047546ffef89c13a175985e320232bacac81cc428"
+                    .to_string(),
+                useraddress: "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q".to_string(),
+            }]),
+            outputs: Some(vec![Output {
+                amount: 1_000_000_000,
+                address: "d929b10b488382de5cccaa2a47b2a77ac04fd78926c67eb264615fc388063ab9"
+                    .to_string(),
+                useraddress: "FA3cih2o2tjEUsnnFR4jX1tQXPpSXFwsp3rhVp6odL5PNCHWvZV1".to_string(),
+            }]),
+            outecs: Some(vec![EcOutput {
+                amount: 10_000_000,
+                address: "4d8fd6215f6cc1245908939c7e6cae1c1189a12fdfe32309c9cf4cee0729c602"
+                    .to_string(),
+                useraddress: "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK".to_string(),
+            }]),
+            rcds: vec![
+                "01718b5edd2914acc2e4677f336c1a32736e5e9bde13663e6413894f57ec272e28".to_string(),
+            ],
+            sigblocks: vec![Sigblock {
+                signatures: vec![WIRE_SIGNATURE.to_string()],
+            }],
+            blockheight: 0,
+        }
+    }
+
+    #[test]
+    fn verify_wire_format_transaction() {
+        let tx = wire_transaction();
+        assert_eq!(hex::encode(tx.marshal_ledger().unwrap()), WIRE_LEDGER);
+        assert_eq!(tx.fee(), 12000);
+        assert!(tx.verify_signatures().unwrap());
+    }
+
+    #[test]
+    fn verify_wire_format_corrupted() {
+        let mut tx = wire_transaction();
+        tx.outputs.as_mut().unwrap()[0].amount += 1;
+        assert!(!tx.verify_signatures().unwrap());
+    }
+
+    const TXID: &str = "774ec19ff567b202ca2702b1f3411901ccae8995df46517c134ab3560100c848";
+    const TXS_JSON: &str = r#"{"transactions":[{"blockheight":220000,"feespaid":12000,
+        "signed":true,"timestamp":1575574473,"totalecoutputs":0,"totalinputs":1000012000,
+        "totaloutputs":1000000000,"inputs":[{"amount":1000012000,
+        "address":"FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q",
+        "useraddress":"FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q"}],
+        "outputs":[{"amount":1000000000,
+        "address":"FA3cih2o2tjEUsnnFR4jX1tQXPpSXFwsp3rhVp6odL5PNCHWvZV1"}],
+        "ecoutputs":[],
+        "txid":"774ec19ff567b202ca2702b1f3411901ccae8995df46517c134ab3560100c848"}]}"#;
+
+    #[test]
+    fn transactions_by_range() {
+        let txs: Transactions = serde_json::from_str(TXS_JSON).unwrap();
+        let result = txs.by_mode(&SearchBy::Range(219000, 221000));
+        assert_eq!(result.blockheights(), vec![Some(220000)]);
+    }
+
+    #[test]
+    fn transactions_summary() {
+        let mut txs: serde_json::Value = serde_json::from_str(TXS_JSON).unwrap();
+        let mut second = txs["transactions"][0].clone();
+        second["txid"] = json!("b".repeat(64));
+        second["totalinputs"] = json!(500_010_000);
+        second["totaloutputs"] = json!(500_000_000);
+        second["feespaid"] = json!(10_000);
+        second["outputs"] = json!(null);
+        txs["transactions"].as_array_mut().unwrap().push(second);
+        let txs: Transactions = serde_json::from_value(txs).unwrap();
+        let summary = txs.summary();
+        assert_eq!(summary.count, 2);
+        assert_eq!(summary.total_inputs, 1_500_022_000);
+        assert_eq!(summary.total_outputs, 1_500_000_000);
+        assert_eq!(summary.total_fees, 22_000);
+    }
+
+    #[test]
+    fn transactions_csv() {
+        let txs: Transactions = serde_json::from_str(TXS_JSON).unwrap();
+        let csv = txs.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("txid,blockheight,timestamp,totalinputs,totaloutputs,feespaid")
+        );
+        let row = format!("{},220000,1575574473,1000012000,1000000000,12000", TXID);
+        assert_eq!(lines.next(), Some(row.as_str()));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn watch_address_new_block() {
+        let heights = |height: i64| {
+            mock::result(
+                json!({"directoryblockheight": height, "leaderheight": height,
+                "entryblockheight": height, "entryheight": height}),
+            )
+        };
+        let existing: serde_json::Value = serde_json::from_str(TXS_JSON).unwrap();
+        let mut incoming = existing["transactions"][0].clone();
+        incoming["txid"] = json!("b".repeat(64));
+        let mut both = existing.clone();
+        both["transactions"].as_array_mut().unwrap().push(incoming);
+        let server = mock::serve(vec![
+            &heights(220000),
+            &mock::result(existing),
+            &heights(220000),
+            &heights(220001),
+            &mock::result(both),
+        ]);
+        let clock = Rc::new(ManualClock::new());
+        let client = Factom::custom_node(&server.url, &server.url).with_clock(clock);
+        let address = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+        let mut txs = watch_address(&client, address, Duration::from_secs(60)).boxed_local();
+        let tx = fetch(txs.next()).unwrap().unwrap();
+        assert_eq!(tx.txid, "b".repeat(64));
+        let requests = server.requests();
+        assert_eq!(mock::method(&requests[1]).1["address"], address);
+    }
+
+    #[test]
+    fn entry_status_both_sections() {
+        let ack = json!({"committxid": "c1", "entryhash": "e1",
+            "commitdata": {"status": "DBlockConfirmed"},
+            "entrydata": {"status": "TransactionACK"}});
+        let server = mock::serve(vec![&mock::result(ack)]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let status = fetch(entry_status(&client, "c1", "e1", "aa")).unwrap();
+        assert_eq!(
+            status,
+            (TxStatus::DBlockConfirmed, TxStatus::TransactionAck)
+        );
+        let (_, params) = mock::method(&server.requests()[0]);
+        assert_eq!(params["hash"], "e1");
+        assert_eq!(params["chainid"], "aa");
+    }
+
+    #[test]
+    fn entry_status_commit_fallback() {
+        let reveal = json!({"committxid": "", "entryhash": "e1",
+            "commitdata": {"status": "Unknown"}, "entrydata": {"status": "Unknown"}});
+        let commit = json!({"committxid": "c1", "entryhash": "",
+            "commitdata": {"status": "TransactionACK"}, "entrydata": {"status": ""}});
+        let server = mock::serve(vec![&mock::result(reveal), &mock::result(commit)]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let status = fetch(entry_status(&client, "c1", "e1", "aa")).unwrap();
+        assert_eq!(status, (TxStatus::TransactionAck, TxStatus::Unknown));
+        let (_, params) = mock::method(&server.requests()[1]);
+        assert_eq!(params["hash"], "c1");
+        assert_eq!(params["chainid"], "c");
+    }
+
+    #[test]
+    fn factomd_transaction_routes_to_factomd() {
+        let server = mock::serve(vec![&mock::error(-32008, "Lookup error")]);
+        let client = Factom::custom_node(&server.url, &mock::closed_port());
+        let response = fetch(factomd_transaction(&client, TXID)).unwrap();
+        assert!(response.is_err());
+        assert_eq!(mock::method(&server.requests()[0]).0, "transaction");
+    }
+
+    #[test]
+    fn wallet_transactions_routes_to_walletd() {
+        let txs: serde_json::Value = serde_json::from_str(TXS_JSON).unwrap();
+        let server = mock::serve(vec![&mock::result(txs)]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let response = fetch(wallet_transactions(&client, SearchBy::Txid(TXID))).unwrap();
+        assert_eq!(response.result.transactions[0].txid, TXID);
+        assert_eq!(mock::method(&server.requests()[0]).0, "transactions");
+    }
+
+    #[test]
+    fn transactions_by_runtime_txid() {
+        let txs: serde_json::Value = serde_json::from_str(TXS_JSON).unwrap();
+        let server = mock::serve(vec![&mock::result(txs)]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let txid = TXID.to_string();
+        let response = fetch(transactions(&client, SearchBy::Txid(&txid))).unwrap();
+        assert_eq!(response.result.transactions[0].txid, txid);
+        let (_, params) = mock::method(&server.requests()[0]);
+        assert_eq!(params["txid"], json!(txid));
+    }
+
+    #[test]
+    fn transactions_by_address() {
+        let txs: Transactions = serde_json::from_str(TXS_JSON).unwrap();
+        let filter = SearchBy::Address("FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q");
+        let result = txs.by_mode(&filter);
+        assert!(matches!(result, TransactionsResult::Address(_)));
+        assert_eq!(result.blockheights(), vec![Some(220000)]);
+    }
+
+    #[test]
+    fn transactions_by_txid() {
+        let txs: Transactions = serde_json::from_str(TXS_JSON).unwrap();
+        let result = txs.by_mode(&SearchBy::Txid(TXID));
+        assert_eq!(result.transactions()[0].feespaid, 12000);
+        assert_eq!(result.blockheights(), vec![None]);
+    }
+
+    #[test]
+    fn pending_tx_ec_outputs() {
+        let json = r#"{"transactionid":"abc","status":"TransactionACK",
+            "inputs":[{"amount":1000012000,"address":"646f3e87",
+            "useraddress":"FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q"}],
+            "outputs":[],"ecoutputs":[{"amount":1000000,"address":"4d8fd621",
+            "useraddress":"EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK"}],
+            "fees":12000}"#;
+        let pending: PendingTx = serde_json::from_str(json).unwrap();
+        let ecoutputs = pending.ecoutputs.unwrap();
+        assert_eq!(ecoutputs[0].amount, 1000000);
+        assert_eq!(
+            ecoutputs[0].useraddress,
+            "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK"
+        );
+    }
+
+    #[test]
+    fn pending_tx_without_ec_outputs() {
+        let json = r#"{"transactionid":"abc","status":"TransactionACK",
+            "inputs":null,"outputs":null,"fees":12000}"#;
+        let pending: PendingTx = serde_json::from_str(json).unwrap();
+        assert!(pending.ecoutputs.is_none());
+    }
+
+    #[test]
+    fn tmp_transaction_names() {
+        let json = r#"{"transactions":[
+            {"tx-name":"payment-0","txid":"a","totalinputs":0,"totaloutputs":0,"totalecoutputs":0},
+            {"tx-name":"payment-1","txid":"b","totalinputs":0,"totaloutputs":0,"totalecoutputs":0}
+            ]}"#;
+        let tmp: TmpTransactions = serde_json::from_str(json).unwrap();
+        assert!(tmp.contains("payment-1"));
+        assert!(!tmp.contains("payment"));
+        assert_eq!(tmp.unique_name("payment"), "payment-2");
+        assert_eq!(
+            TmpTransactions::default().unique_name("payment"),
+            "payment-0"
+        );
+    }
+
+    #[test]
+    fn new_transaction_name_collision() {
+        let tmp = json!({"transactions": [{"tx-name": "payment-0", "txid": "a",
+            "totalinputs": 0, "totaloutputs": 0, "totalecoutputs": 0}]});
+        let server = mock::serve(vec![&mock::result(tmp)]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let err = fetch(new_transaction(&client, "payment-0")).unwrap_err();
+        match err.downcast_ref::<FetchError>() {
+            Some(FetchError::TransactionNameExists(name)) => assert_eq!(name, "payment-0"),
+            _ => panic!("Expected a TransactionNameExists error"),
+        }
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(mock::method(&requests[0]).0, "tmp-transactions");
+    }
+
+    #[test]
+    fn transaction_factoid_shape() {
+        let json = r#"{"factoidtransaction":{"millitimestamp":1575574473164,
+            "inputs":null,"outputs":null,"outecs":null,"rcds":[],"sigblocks":[],
+            "blockheight":0},"includedintransactionblock":"aa",
+            "includedindirectoryblock":"bb","includedindirectoryblockheight":220000}"#;
+        match serde_json::from_str::<TransactionOrEntry>(json).unwrap() {
+            TransactionOrEntry::Factoid(tx) => {
+                assert_eq!(tx.includedintransactionblock, "aa");
+                assert_eq!(tx.factoidtransaction.millitimestamp, 1575574473164);
+            }
+            other => panic!("expected factoid transaction, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn transaction_entry_shape() {
+        let json = r#"{"includedinentryblock":"cc","includedindirectoryblock":"bb",
+            "includedindirectoryblockheight":220000}"#;
+        match serde_json::from_str::<TransactionOrEntry>(json).unwrap() {
+            TransactionOrEntry::Entry(entry) => {
+                assert_eq!(entry.includedinentryblock, "cc");
+                assert_eq!(entry.includedindirectoryblockheight, 220000);
+            }
+            other => panic!("expected entry, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn confirmed_pending_transactions() {
+        let pending = |id: &str, status: &str| PendingTx {
+            transactionid: id.to_string(),
+            status: status.to_string(),
+            ..Default::default()
+        };
+        let txs = vec![
+            pending("a", "TransactionACK"),
+            pending("b", "DBlockConfirmed"),
+            pending("c", "NotConfirmed"),
+            pending("d", "DBlockConfirmed"),
+            pending("e", "Unknown"),
+        ];
+        let ids: Vec<String> = confirmed_only(txs)
+            .into_iter()
+            .map(|tx| tx.transactionid)
+            .collect();
+        assert_eq!(ids, vec!["b", "d"]);
+        assert_eq!(
+            TxStatus::from("Holding"),
+            TxStatus::Other("Holding".to_string())
+        );
+    }
+
+    #[test]
+    fn fees_summed() {
+        let first = signed_transaction();
+        let mut second = signed_transaction();
+        second.outecs = Some(vec![EcOutput {
+            amount: 2000,
+            ..Default::default()
+        }]);
+        assert_eq!(first.fee(), 12000);
+        assert_eq!(second.fee(), 10000);
+        let fees = vec![
+            ("a".to_string(), Ok(first.fee())),
+            ("b".to_string(), Ok(second.fee())),
+            ("c".to_string(), Err(FetchError::Timeout.into())),
+        ];
+        let total = sum_fees(fees);
+        assert_eq!(total.total, 22000);
+        assert_eq!(total.failed.len(), 1);
+        assert_eq!(total.failed[0].0, "c");
+    }
+
+    #[test]
+    fn signed_bool_or_int() {
+        let body = |signed: &str| {
+            format!(
+                r#"{{"signed":{},"name":"tx","timestamp":0,"totalecoutputs":0,
+                "totalinputs":0,"totaloutputs":0,"inputs":null,"outputs":null,
+                "ecoutputs":null,"txid":"abc"}}"#,
+                signed
+            )
+        };
+        let parse = |signed| serde_json::from_str::<DeleteTx>(&body(signed));
+        assert!(parse("true").unwrap().signed);
+        assert!(!parse("false").unwrap().signed);
+        assert!(parse("1").unwrap().signed);
+        assert!(!parse("0").unwrap().signed);
+        assert!(parse("2").is_err());
+    }
+
+    #[test]
+    fn resubmit_confirms() {
+        let clock = Rc::new(ManualClock::new());
+        let client = Factom::new().with_clock(clock);
+        let submits = Cell::new(0);
+        let submit = || {
+            submits.set(submits.get() + 1);
+            async { Ok(TXID.to_string()) }
+        };
+        let ack = |txid: String| {
+            let status = if submits.get() == 1 {
+                TxStatus::TransactionAck
+            } else {
+                TxStatus::DBlockConfirmed
+            };
+            async move {
+                assert_eq!(txid, TXID);
+                Ok(status)
+            }
+        };
+        let poll = Duration::from_secs(10);
+        let query = submit_until_confirmed(&client, poll, Duration::from_secs(60), submit, ack);
+        assert_eq!(fetch(query).unwrap(), TxStatus::DBlockConfirmed);
+        assert_eq!(submits.get(), 2);
+    }
+
+    #[test]
+    fn resubmit_gives_up() {
+        let clock = Rc::new(ManualClock::new());
+        let client = Factom::new().with_clock(clock);
+        let submits = Cell::new(0);
+        let submit = || {
+            submits.set(submits.get() + 1);
+            async { Ok(TXID.to_string()) }
+        };
+        let ack = |_: String| async { Ok(TxStatus::TransactionAck) };
+        let poll = Duration::from_secs(10);
+        let query = submit_until_confirmed(&client, poll, Duration::from_secs(60), submit, ack);
+        assert_eq!(fetch(query).unwrap(), TxStatus::TransactionAck);
+        assert_eq!(submits.get(), 2);
+    }
+
+    #[test]
+    fn depth_from_head() {
+        let server = mock::serve_with(4, |request| match mock::method(request).0.as_str() {
+            "heights" => mock::result(json!({"directoryblockheight": 220010,
+                "leaderheight": 220011, "entryblockheight": 220010, "entryheight": 220010})),
+            _ => {
+                let (_, params) = mock::method(request);
+                let height = if params["hash"] == json!(TXID) {
+                    220000
+                } else {
+                    -1
+                };
+                let factoidtransaction = json!({"millitimestamp": 1, "inputs": null,
+                    "outputs": null, "rcds": [], "sigblocks": [], "blockheight": 0});
+                mock::result(json!({"factoidtransaction": factoidtransaction,
+                    "includedintransactionblock": "", "includedindirectoryblock": "",
+                    "includedindirectoryblockheight": height}))
+            }
+        });
+        let client = Factom::custom_node(&server.url, &server.url);
+        assert_eq!(fetch(confirmation_depth(&client, TXID)).unwrap(), Some(10));
+        assert_eq!(fetch(confirmation_depth(&client, "pending")).unwrap(), None);
+        server.requests();
+    }
+
+    #[test]
+    fn transaction_unexpected_shape() {
+        let body = mock::result(json!({"includedindirectoryblock": "",
+            "includedindirectoryblockheight": 5}));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let err = fetch(transaction(&client, TXID)).unwrap_err();
+        match err.downcast_ref::<FetchError>() {
+            Some(FetchError::UnexpectedShape(field)) => assert_eq!(field, "factoidtransaction"),
+            _ => panic!("Expected an UnexpectedShape error"),
+        }
+        server.requests();
+    }
+
+    #[test]
+    fn varint_encoding() {
+        let mut out = Vec::new();
+        encode_varint(1_000_000_000, &mut out);
+        assert_eq!(out, vec![0x83, 0xdc, 0xeb, 0x94, 0x00]);
+    }
+
+    #[test]
+    fn amount_u64_valid() {
+        let input = TxInput {
+            address: "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q".to_string(),
+            amount: 1_000_000_000,
+        };
+        assert_eq!(input.amount_u64().unwrap(), 1_000_000_000u64);
+    }
+
+    #[test]
+    fn amount_u64_negative() {
+        let output = TxOutput {
+            address: "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q".to_string(),
+            amount: -1,
+        };
+        let err = output.amount_u64().unwrap_err();
+        match err.downcast_ref::<FetchError>() {
+            Some(FetchError::NegativeAmount(amount)) => assert_eq!(*amount, -1),
+            _ => panic!("Expected a NegativeAmount error"),
+        }
+    }
+}
@@ -2,6 +2,11 @@
 use super::*;
 use crate::chain::RevealChain;
 use crate::entry::{Entry, RevealEntry};
+use futures::stream::{self, StreamExt};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
 use std::{thread, time};
 
 /// Creates a chain going through the entire compose, commit, reveal workflow
@@ -78,6 +83,105 @@ pub async fn create_entry(
     reveal_response
 }
 
+/// Whether each daemon could be reached, holding the error if not
+#[derive(Debug)]
+pub struct DaemonHealth {
+    pub factomd: Result<()>,
+    pub walletd: Result<()>,
+}
+
+/// Checks factomd and walletd concurrently by requesting their properties.
+/// Unlike a simple boolean check the error for each daemon is kept, so it can
+/// be logged or matched on to find out why a daemon is down.
+/// # Example
+/// ```
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let health = utils::daemons_reachable(&client).await;
+///   assert!(health.factomd.is_ok());
+///   if let Err(e) = health.walletd {
+///     println!("walletd is down: {}", e);
+///   }
+/// }
+/// ```
+pub async fn daemons_reachable(api: &Factom) -> DaemonHealth {
+    let (factomd, walletd) =
+        futures::join!(factomd::properties(api), walletd::wallet_properties(api));
+    DaemonHealth {
+        factomd: factomd.and_then(|response| response.api_result().map(|_| ())),
+        walletd: walletd.and_then(|response| response.api_result().map(|_| ())),
+    }
+}
+
+/// The parts of an entry to be created with create_entries
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct EntrySpec {
+    pub chainid: String,
+    pub extids: Vec<String>,
+    pub content: String,
+}
+
+/// Creates a batch of entries, each going through the compose, commit, reveal
+/// workflow with up to MAX_CONCURRENT_REQUESTS entries in flight at once. A
+/// result is returned for every entry in the order given so failures can be
+/// retried individually.
+///
+/// Repeated commit errors are tolerated and the entry is revealed, any other
+/// api error while composing or committing is returned as a FetchError::Api.
+///
+/// # Example
+/// ```no_run
+///  use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+/// let client = Factom::new();
+/// let chainid = "72a2fa10b81a8bffde58ea206254f0eaa7928e9e09a4144efb3ba0bb7be26d52";
+/// let entries = (0..3).map(|i| utils::EntrySpec {
+///   chainid: chainid.to_string(),
+///   extids: vec!["Api Client".to_string()],
+///   content: format!("Entry {}", i),
+/// }).collect();
+/// let ec_pub = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
+/// let responses = utils::create_entries(&client, entries, ec_pub).await;
+/// dbg!(responses);
+/// }
+/// ```
+pub async fn create_entries(
+    client: &Factom,
+    entries: Vec<EntrySpec>,
+    ec_pub: &str,
+) -> Vec<Result<ApiResponse<RevealEntry>>> {
+    stream::iter(entries)
+        .map(|spec| create_entry_from_spec(client, spec, ec_pub))
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await
+}
+
+async fn create_entry_from_spec(
+    client: &Factom,
+    spec: EntrySpec,
+    ec_pub: &str,
+) -> Result<ApiResponse<RevealEntry>> {
+    let ext_ids = spec.extids.iter().map(String::as_str).collect();
+    let compose_query =
+        compose::compose_entry(client, &spec.chainid, ext_ids, &spec.content, ec_pub);
+    let composed = compose_query.await?.api_result()?;
+    let commit_response = entry::commit_entry(client, &composed.commit.params.message).await?;
+    if commit_response.is_err() && commit_response.error.code != REPEATED_COMMIT {
+        return Err(FetchError::Api(commit_response.error).into());
+    }
+    client
+        .clock
+        .sleep(Duration::from_millis(REVEAL_DELAY_MS))
+        .await;
+    entry::reveal_entry(client, &composed.reveal.params.entry).await
+}
+
 /// Creates a chain going through the entire compose, commit, reveal workflow
 ///
 /// # Example
@@ -156,6 +260,217 @@ pub async fn traverse_chain(client: &Factom, chainid: &str, depth: usize) -> Vec
     entries
 }
 
+/// Fetches every entry in a chain, walking the entry blocks back from the chain
+/// head. Unlike traverse_chain errors are returned rather than panicking and
+/// the entries are returned oldest first.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let chain = "843dbee7a49a9b9510d399759fbce24b1f700268c94508085abce352d70ed1f6";
+///   let entries = utils::chain_entries(&client, chain).await.unwrap();
+///   dbg!(entries);
+/// }
+/// ```
+pub async fn chain_entries(client: &Factom, chainid: &str) -> Result<Vec<Entry>> {
+    let entries = entries_until(client, chainid, None).await?;
+    Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+}
+
+/// Fetches the entries added to a chain after a known entry, for polling a
+/// chain incrementally. Entry blocks are walked back from the chain head until
+/// the known entry hash is reached, only the newer entries are returned,
+/// oldest first. If the entry hash is never found the whole chain is returned.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let chain = "843dbee7a49a9b9510d399759fbce24b1f700268c94508085abce352d70ed1f6";
+///   let last_seen = "e6c8b95d2eeb4a49bbcb8f6c8d3f5a1fb0ef08d6a9d50e3ec4bfc6e8aba0a6f3";
+///   let entries = utils::chain_entries_since(&client, chain, last_seen).await.unwrap();
+///   dbg!(entries);
+/// }
+/// ```
+pub async fn chain_entries_since(
+    client: &Factom,
+    chainid: &str,
+    since_entryhash: &str,
+) -> Result<Vec<Entry>> {
+    let entries = entries_until(client, chainid, Some(since_entryhash)).await?;
+    Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+}
+
+/// Walks a chain back from its head fetching entries along with their entry
+/// hashes, stopping at the given entry hash if any. Entries are returned oldest
+/// first.
+async fn entries_until(
+    client: &Factom,
+    chainid: &str,
+    stop: Option<&str>,
+) -> Result<Vec<(String, Entry)>> {
+    let head = chain::chain_head(client, chainid).await?.api_result()?;
+    let mut keymr = head.chainhead;
+    let mut entries = Vec::new();
+    'blocks: while keymr != NULL_KEYMR {
+        let eblock = block::entry_block(client, &keymr).await?.api_result()?;
+        for e in eblock.entrylist.iter().rev() {
+            if Some(e.entryhash.as_str()) == stop {
+                break 'blocks;
+            }
+            let entry = entry::entry(client, &e.entryhash).await?.api_result()?;
+            entries.push((e.entryhash.clone(), entry));
+        }
+        keymr = eblock.header.prevkeymr;
+    }
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Finds entries in a chain with identical content and external ids. Returns
+/// each content hash shared by more than one entry along with the entry hashes
+/// sharing it, in chain order. Every entry is fetched so this can be slow for
+/// long chains.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let chain = "843dbee7a49a9b9510d399759fbce24b1f700268c94508085abce352d70ed1f6";
+///   let duplicates = utils::find_duplicate_entries(&client, chain).await.unwrap();
+///   for (content_hash, entryhashes) in duplicates {
+///     println!("{} is shared by {:?}", content_hash, entryhashes);
+///   }
+/// }
+/// ```
+pub async fn find_duplicate_entries(
+    client: &Factom,
+    chainid: &str,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let entries = entries_until(client, chainid, None).await?;
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (entryhash, entry) in entries {
+        let content_hash = entry_content_hash(&entry);
+        match index.get(&content_hash) {
+            Some(&i) => groups[i].1.push(entryhash),
+            None => {
+                index.insert(content_hash.clone(), groups.len());
+                groups.push((content_hash, vec![entryhash]));
+            }
+        }
+    }
+    groups.retain(|(_, entryhashes)| entryhashes.len() > 1);
+    Ok(groups)
+}
+
+/// Hex sha256 of an entry's external ids and content, each prefixed with its
+/// length so different splits of the same bytes don't collide
+fn entry_content_hash(entry: &Entry) -> String {
+    let mut hasher = Sha256::new();
+    for field in entry.extids.iter().chain(std::iter::once(&entry.content)) {
+        hasher.input((field.len() as u64).to_be_bytes());
+        hasher.input(field.as_bytes());
+    }
+    hex::encode(hasher.result())
+}
+
+/// Reads a chain used as an append only key/value log, where each entry's first
+/// external id is the key and its content the value. Entries are applied
+/// oldest first so the latest value for a key wins, entries without external
+/// ids are skipped. Keys and values are the hex decoded bytes.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let chain = "843dbee7a49a9b9510d399759fbce24b1f700268c94508085abce352d70ed1f6";
+///   let state = utils::chain_kv_state(&client, chain).await.unwrap();
+///   for (key, value) in state {
+///     println!("{}: {}", String::from_utf8_lossy(&key), String::from_utf8_lossy(&value));
+///   }
+/// }
+/// ```
+pub async fn chain_kv_state(client: &Factom, chainid: &str) -> Result<HashMap<Vec<u8>, Vec<u8>>> {
+    let mut state = HashMap::new();
+    for entry in chain_entries(client, chainid).await? {
+        if let Some(key) = entry.extids.first() {
+            state.insert(hex::decode(key)?, hex::decode(&entry.content)?);
+        }
+    }
+    Ok(state)
+}
+
+/// Totals the entry credits spent on a chain, the cost of each entry plus the
+/// chain creation cost paid with the first entry. Every entry is fetched with
+/// chain_entries so this can be slow for long chains.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let chain = "843dbee7a49a9b9510d399759fbce24b1f700268c94508085abce352d70ed1f6";
+///   let spent = utils::chain_ec_spent(&client, chain).await.unwrap();
+///   println!("Entry credits spent: {}", spent);
+/// }
+/// ```
+pub async fn chain_ec_spent(client: &Factom, chainid: &str) -> Result<u64> {
+    let entries = chain_entries(client, chainid).await?;
+    if entries.is_empty() {
+        return Ok(0);
+    }
+    let mut spent = CHAIN_CREATION_EC;
+    for entry in entries.iter() {
+        spent += entry.ec_cost()?;
+    }
+    Ok(spent)
+}
+
+/// Fetches the entries of several chains concurrently with chain_entries. Each
+/// chain's result is kept separately so one failing chain doesn't affect the
+/// others.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let chains = ["843dbee7a49a9b9510d399759fbce24b1f700268c94508085abce352d70ed1f6"];
+///   let results = utils::chains_entries(&client, &chains).await;
+///   for (chainid, entries) in results {
+///     println!("{}: {:?}", chainid, entries.map(|e| e.len()));
+///   }
+/// }
+/// ```
+pub async fn chains_entries(
+    client: &Factom,
+    chainids: &[&str],
+) -> HashMap<String, Result<Vec<Entry>>> {
+    stream::iter(chainids)
+        .map(|chainid| async move { (chainid.to_string(), chain_entries(client, chainid).await) })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await
+}
+
 /// Converts Factoshis to Factoids
 pub fn factoshis_to_fct(factoshis: usize) -> f64 {
     factoshis as f64 / 100_000_000f64
@@ -165,3 +480,387 @@ pub fn factoshis_to_fct(factoshis: usize) -> f64 {
 pub fn fct_to_factoshis(factoids: f64) -> f64 {
     factoids * 100_000_000f64
 }
+
+/// Converts a signed amount returned by the api into an unsigned one, returning
+/// a FetchError::NegativeAmount if the daemon reported a negative value.
+pub fn non_negative(amount: i64) -> Result<u64> {
+    if amount < 0 {
+        return Err(FetchError::NegativeAmount(amount).into());
+    }
+    Ok(amount as u64)
+}
+
+/// Double sha256 hash, used for RCD hashes and address checksums
+pub fn double_sha(input: &[u8]) -> [u8; 32] {
+    let first = Sha256::digest(input);
+    let second = Sha256::digest(&first);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&second);
+    out
+}
+
+/// Decodes a hex string returned by the api into bytes
+pub fn decode_hex(input: &str) -> Result<Vec<u8>> {
+    hex::decode(input).map_err(|e| FetchError::Malformed(format!("{}: {}", input, e)).into())
+}
+
+/// Deserializes a boolean which some daemon versions send as 0 or 1, for use
+/// with `#[serde(deserialize_with = "utils::bool_or_int")]`
+pub fn bool_or_int<'de, D>(deserializer: D) -> std::result::Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrInt {
+        Bool(bool),
+        Int(u64),
+    }
+    match BoolOrInt::deserialize(deserializer)? {
+        BoolOrInt::Bool(value) => Ok(value),
+        BoolOrInt::Int(0) => Ok(false),
+        BoolOrInt::Int(1) => Ok(true),
+        BoolOrInt::Int(other) => Err(serde::de::Error::custom(format!(
+            "expected a boolean or 0/1, found {}",
+            other
+        ))),
+    }
+}
+
+/// Deserializes an object which factomd sends as false or null when it doesn't
+/// exist yet, for use with `#[serde(default, deserialize_with = "utils::false_as_none")]`
+pub fn false_as_none<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OrFalse<T> {
+        Bool(bool),
+        Value(T),
+    }
+    match Option::<OrFalse<T>>::deserialize(deserializer)? {
+        None | Some(OrFalse::Bool(false)) => Ok(None),
+        Some(OrFalse::Value(value)) => Ok(Some(value)),
+        Some(OrFalse::Bool(true)) => Err(serde::de::Error::custom(
+            "expected an object, null or false, found true",
+        )),
+    }
+}
+
+/// Deserializes null as the default value, for use with
+/// `#[serde(deserialize_with = "utils::null_as_default")]` on fields which
+/// walletd returns as null rather than empty
+pub fn null_as_default<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Repeatedly runs a check every poll interval until it returns Some, using the
+/// client's clock. Returns a FetchError::Timeout if the timeout elapses first,
+/// errors from the check are returned immediately.
+///
+/// # Example
+/// ```no_run
+/// use factom::*;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::open_node();
+///   let poll = Duration::from_secs(10);
+///   let timeout = Duration::from_secs(600);
+///   let height = utils::poll_until(&client, poll, timeout, || async {
+///     let response = factomd::heights(&client).await.expect("Fetching heights");
+///     let height = response.result.directoryblockheight;
+///     Ok(if height > 220000 { Some(height) } else { None })
+///   }).await;
+///   dbg!(height);
+/// }
+/// ```
+pub async fn poll_until<F, Fut, T>(
+    api: &Factom,
+    poll: Duration,
+    timeout: Duration,
+    mut check: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    let start = api.clock.now();
+    loop {
+        if let Some(value) = check().await? {
+            return Ok(value);
+        }
+        if api.clock.now() - start >= timeout {
+            return Err(FetchError::Timeout.into());
+        }
+        api.clock.sleep(poll).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use std::cell::Cell;
+
+    #[test]
+    fn walletd_unreachable() {
+        let body = r#"{"jsonrpc":"2.0","id":0,"result":{"factomdversion":"6.5.0",
+            "factomdapiversion":"2.0"}}"#;
+        let factomd = mock::serve(vec![body]);
+        let client = Factom::custom_node(&factomd.url, &mock::closed_port());
+        let health = fetch(daemons_reachable(&client));
+        assert!(health.factomd.is_ok());
+        let err = health.walletd.unwrap_err();
+        assert!(err.downcast_ref::<hyper::Error>().is_some());
+        factomd.requests();
+    }
+
+    #[test]
+    fn create_entries_isolated() {
+        let failing = factomd::str_to_hex("two");
+        let server = mock::serve_with(7, move |request| {
+            let (method, params) = mock::method(request);
+            match method.as_str() {
+                "compose-entry" => {
+                    let content = params["entry"]["content"].as_str().unwrap().to_string();
+                    if content == failing {
+                        return mock::error(-32603, "Internal error");
+                    }
+                    mock::result(json!({
+                        "commit": {"jsonrpc": "2.0", "id": 0, "method": "commit-entry",
+                            "params": {"message": content}},
+                        "reveal": {"jsonrpc": "2.0", "id": 0, "method": "reveal-entry",
+                            "params": {"entry": content}}
+                    }))
+                }
+                "commit-entry" => mock::result(json!({
+                    "message": "Entry Commit Success",
+                    "txid": "aa",
+                    "entryhash": params["message"]
+                })),
+                _ => mock::result(json!({
+                    "message": "Entry Reveal Success",
+                    "entryhash": params["entry"],
+                    "chainid": "bb"
+                })),
+            }
+        });
+        let client =
+            Factom::custom_node(&server.url, &server.url).with_clock(Rc::new(ManualClock::new()));
+        let entries = ["one", "two", "three"]
+            .iter()
+            .map(|content| EntrySpec {
+                chainid: "bb".to_string(),
+                extids: vec!["Api Client".to_string()],
+                content: content.to_string(),
+            })
+            .collect();
+        let results = fetch(create_entries(&client, entries, "EC"));
+        let revealed = |i: usize| results[i].as_ref().unwrap().result.entryhash.clone();
+        assert_eq!(revealed(0), factomd::str_to_hex("one"));
+        assert!(matches!(
+            results[1]
+                .as_ref()
+                .unwrap_err()
+                .downcast_ref::<FetchError>(),
+            Some(FetchError::Api(_))
+        ));
+        assert_eq!(revealed(2), factomd::str_to_hex("three"));
+        assert_eq!(server.requests().len(), 7);
+    }
+
+    #[test]
+    fn chains_entries_isolated() {
+        let good = "aa".repeat(32);
+        let bad = "bb".repeat(32);
+        let chain = good.clone();
+        let server = mock::serve_with(5, move |request| {
+            let (method, params) = mock::method(request);
+            match method.as_str() {
+                "chain-head" if params["chainid"] == json!(chain) => {
+                    mock::result(json!({"chainhead": "cc", "chaininprocesslist": false}))
+                }
+                "chain-head" => mock::error(-32009, "Missing Chain Head"),
+                "entry-block" => mock::result(json!({
+                    "header": {"blocksequencenumber": 0, "chainid": chain, "prevkeymr": NULL_KEYMR,
+                        "timestamp": 0, "dbheight": 1},
+                    "entrylist": [{"entryhash": "e1", "timestamp": 0},
+                        {"entryhash": "e2", "timestamp": 0}]
+                })),
+                _ => {
+                    let content = params["hash"].as_str().unwrap().to_string();
+                    mock::result(json!({"chainid": chain, "content": content, "extids": []}))
+                }
+            }
+        });
+        let client = Factom::custom_node(&server.url, &server.url);
+        let ids = [good.as_str(), bad.as_str()];
+        let query = chains_entries(&client, &ids);
+        let results = fetch(query);
+        let entries = results[&good].as_ref().unwrap();
+        let contents: Vec<&str> = entries.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, vec!["e1", "e2"]);
+        assert!(results[&bad].is_err());
+        server.requests();
+    }
+
+    #[test]
+    fn entries_since_marker() {
+        let chain = "aa".repeat(32);
+        let server = mock::serve_with(4, |request| {
+            let (method, params) = mock::method(request);
+            match method.as_str() {
+                "chain-head" => {
+                    mock::result(json!({"chainhead": "cc", "chaininprocesslist": false}))
+                }
+                "entry-block" => mock::result(json!({
+                    "header": {"blocksequencenumber": 0, "chainid": "aa", "prevkeymr": NULL_KEYMR,
+                        "timestamp": 0, "dbheight": 1},
+                    "entrylist": [{"entryhash": "e1", "timestamp": 0},
+                        {"entryhash": "e2", "timestamp": 0}, {"entryhash": "e3", "timestamp": 0}]
+                })),
+                _ => {
+                    let content = params["hash"].as_str().unwrap().to_string();
+                    mock::result(json!({"chainid": "aa", "content": content, "extids": []}))
+                }
+            }
+        });
+        let client = Factom::custom_node(&server.url, &server.url);
+        let entries = fetch(chain_entries_since(&client, &chain, "e1")).unwrap();
+        let contents: Vec<&str> = entries.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, vec!["e2", "e3"]);
+        assert_eq!(server.requests().len(), 4);
+    }
+
+    #[test]
+    fn chain_ec_spent_total() {
+        let chain = "aa".repeat(32);
+        let server = mock::serve_with(4, |request| {
+            let (method, params) = mock::method(request);
+            match method.as_str() {
+                "chain-head" => {
+                    mock::result(json!({"chainhead": "cc", "chaininprocesslist": false}))
+                }
+                "entry-block" => mock::result(json!({
+                    "header": {"blocksequencenumber": 0, "chainid": "aa", "prevkeymr": NULL_KEYMR,
+                        "timestamp": 0, "dbheight": 1},
+                    "entrylist": [{"entryhash": "e1", "timestamp": 0},
+                        {"entryhash": "e2", "timestamp": 0}]
+                })),
+                _ => match params["hash"].as_str().unwrap() {
+                    "e1" => mock::result(json!({"chainid": "aa", "content": "00".repeat(1100),
+                        "extids": ["6964"]})),
+                    _ => mock::result(json!({"chainid": "aa", "content": "", "extids": []})),
+                },
+            }
+        });
+        let client = Factom::custom_node(&server.url, &server.url);
+        assert_eq!(fetch(chain_ec_spent(&client, &chain)).unwrap(), 2 + 1 + 10);
+        server.requests();
+    }
+
+    #[test]
+    fn duplicate_entries_grouped() {
+        let chain = "aa".repeat(32);
+        let server = mock::serve_with(5, |request| {
+            let (method, params) = mock::method(request);
+            match method.as_str() {
+                "chain-head" => {
+                    mock::result(json!({"chainhead": "cc", "chaininprocesslist": false}))
+                }
+                "entry-block" => mock::result(json!({
+                    "header": {"blocksequencenumber": 0, "chainid": "aa", "prevkeymr": NULL_KEYMR,
+                        "timestamp": 0, "dbheight": 1},
+                    "entrylist": [{"entryhash": "e1", "timestamp": 0},
+                        {"entryhash": "e2", "timestamp": 0}, {"entryhash": "e3", "timestamp": 0}]
+                })),
+                _ => {
+                    let content = match params["hash"].as_str().unwrap() {
+                        "e2" => "0102",
+                        _ => "0a0b",
+                    };
+                    mock::result(json!({"chainid": "aa", "content": content, "extids": ["01"]}))
+                }
+            }
+        });
+        let client = Factom::custom_node(&server.url, &server.url);
+        let duplicates = fetch(find_duplicate_entries(&client, &chain)).unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].1, vec!["e1".to_string(), "e3".to_string()]);
+        server.requests();
+    }
+
+    #[test]
+    fn kv_state_latest_wins() {
+        let chain = "aa".repeat(32);
+        let server = mock::serve_with(6, |request| {
+            let (method, params) = mock::method(request);
+            match method.as_str() {
+                "chain-head" => {
+                    mock::result(json!({"chainhead": "cc", "chaininprocesslist": false}))
+                }
+                "entry-block" => mock::result(json!({
+                    "header": {"blocksequencenumber": 0, "chainid": "aa", "prevkeymr": NULL_KEYMR,
+                        "timestamp": 0, "dbheight": 1},
+                    "entrylist": [{"entryhash": "e1", "timestamp": 0},
+                        {"entryhash": "e2", "timestamp": 0}, {"entryhash": "e3", "timestamp": 0},
+                        {"entryhash": "e4", "timestamp": 0}]
+                })),
+                _ => {
+                    let (extids, content) = match params["hash"].as_str().unwrap() {
+                        "e1" => (json!(["6b31"]), "7631"),
+                        "e2" => (json!(["6b32"]), "7632"),
+                        "e3" => (json!([]), "ff"),
+                        _ => (json!(["6b31", "00"]), "7633"),
+                    };
+                    mock::result(json!({"chainid": "aa", "content": content, "extids": extids}))
+                }
+            }
+        });
+        let client = Factom::custom_node(&server.url, &server.url);
+        let state = fetch(chain_kv_state(&client, &chain)).unwrap();
+        assert_eq!(state.len(), 2);
+        assert_eq!(state[&b"k1".to_vec()], b"v3".to_vec());
+        assert_eq!(state[&b"k2".to_vec()], b"v2".to_vec());
+        server.requests();
+    }
+
+    #[test]
+    fn poll_with_manual_clock() {
+        let clock = Rc::new(ManualClock::new());
+        let client = Factom::new().with_clock(clock.clone());
+        let start = clock.now();
+        let attempts = Cell::new(0);
+        let poll = Duration::from_secs(10);
+        let query = poll_until(&client, poll, Duration::from_secs(60), || {
+            attempts.set(attempts.get() + 1);
+            let done = attempts.get() == 3;
+            async move { Ok(if done { Some("done") } else { None }) }
+        });
+        assert_eq!(fetch(query).unwrap(), "done");
+        assert_eq!(clock.now() - start, Duration::from_secs(20));
+    }
+
+    #[test]
+    fn poll_times_out() {
+        let clock = Rc::new(ManualClock::new());
+        let client = Factom::new().with_clock(clock);
+        let poll = Duration::from_secs(10);
+        let query = poll_until(&client, poll, Duration::from_secs(30), || async {
+            Ok(None::<()>)
+        });
+        let err = fetch(query).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FetchError>(),
+            Some(FetchError::Timeout)
+        ));
+    }
+}
@@ -1,8 +1,13 @@
 //! General utility functions relating to factom-walletd
 use super::*;
+use futures::stream::{self, StreamExt};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::time::Duration;
 
 /// Return the wallet seed and all addresses in the wallet for backup and offline
-/// storage.
+/// storage. The response holds every secret in the wallet so handle it with
+/// care, if the wallet is encrypted it must be unlocked first.
 /// # Example
 /// ```
 /// use factom::*;
@@ -11,8 +16,8 @@ use super::*;
 /// async fn main() {
 ///   let client = Factom::new();
 ///   let response = walletd::wallet_backup(&client).await.unwrap();
-///   dbg!(&response);
 ///   assert!(response.success());
+///   assert!(!response.result.wallet_seed.is_empty());
 /// }
 /// ```
 pub async fn wallet_backup(api: &Factom) -> Result<ApiResponse<WalletBackup>> {
@@ -46,20 +51,34 @@ pub async fn wallet_backup(api: &Factom) -> Result<ApiResponse<WalletBackup>> {
 ///
 /// * "ecaccountbalances" are the total of all entry credit account balances
 /// returned in entry credits.
+///
+/// * An empty wallet returns zero balances rather than an error.
 pub async fn wallet_balances(api: &Factom) -> Result<ApiResponse<WalletBalances>> {
     let req = ApiRequest::new("wallet-balances");
     let response = walletd_call(api, req).await;
-    parse(response).await
+    Ok(parse(response).await?.or_empty_wallet())
 }
 
 ///  Unlocks this wallet for the amount of time specified in seconds by timeout.
-///  The maximum amount of time a wallet can be unlocked for is 230 seconds (Roughly
-///  34 Years… Give or take a decade). This command will only work on wallets that
-///  are encrypted. If successful, returns the expiration time of your access as a
-///  Unix timestamp.
+///  The maximum amount of time a wallet can be unlocked for is 2^30 seconds
+///  (Roughly 34 Years… Give or take a decade). This command will only work on
+///  wallets that are encrypted. If successful, returns the expiration time of
+///  your access as a Unix timestamp.
 ///
 /// While the wallet is locked, the only accessible RPC API commands are get-height,
 /// properties, transactions, and unlock-wallet.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let response = walletd::unlock_wallet(&client, "passphrase", 60).await.unwrap();
+///   assert!(response.result.success);
+///   println!("Unlocked until {}", response.result.unlockeduntil);
+/// }
+/// ```
 pub async fn unlock_wallet(
     api: &Factom,
     passphrase: &str,
@@ -73,7 +92,27 @@ pub async fn unlock_wallet(
     parse(response).await
 }
 
-/// Get the current hight of blocks that have been cached by the wallet while syncing.
+/// Locks an encrypted wallet again before its unlock timeout expires. Walletd
+/// versions without the lock-wallet call return a method not found api error.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   walletd::unlock_wallet(&client, "passphrase", 60).await.unwrap();
+///   let response = walletd::lock_wallet(&client).await.unwrap();
+///   assert!(response.result.success);
+/// }
+/// ```
+pub async fn lock_wallet(api: &Factom) -> Result<ApiResponse<LockWallet>> {
+    let req = ApiRequest::new("lock-wallet");
+    let response = walletd_call(api, req).await;
+    parse(response).await
+}
+
+/// Get the current height of blocks that have been cached by the wallet while syncing.
 /// # Example
 /// ```
 /// use factom::*;
@@ -83,15 +122,72 @@ pub async fn unlock_wallet(
 ///   let client = Factom::open_node();
 ///   let response = walletd::wallet_height(&client).await.unwrap();
 ///   dbg!(&response);
-///   assert!(response.success());
+///   assert!(response.result.height >= 0);
 /// }
 /// ```
-pub async fn wallet_height(api: &Factom) -> Result<ApiResponse<Height>> {
+pub async fn wallet_height(api: &Factom) -> Result<ApiResponse<WalletHeight>> {
     let req = ApiRequest::new("get-height");
     let response = walletd_call(api, req).await;
     parse(response).await
 }
 
+/// Waits until the wallet has cached blocks up to the target height, polling
+/// get-height every poll interval. When no target is given the current factomd
+/// directory block height is used. Returns a FetchError::Timeout if the wallet
+/// has not synced before the timeout elapses.
+/// # Example
+/// ```no_run
+/// use factom::*;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let poll = Duration::from_secs(5);
+///   let timeout = Duration::from_secs(600);
+///   walletd::wait_for_wallet_sync(&client, None, poll, timeout).await.unwrap();
+/// }
+/// ```
+pub async fn wait_for_wallet_sync(
+    api: &Factom,
+    target: Option<u32>,
+    poll: Duration,
+    timeout: Duration,
+) -> Result<()> {
+    let target = match target {
+        Some(target) => target,
+        None => {
+            let heights = factomd::heights(api).await?.api_result()?;
+            heights.directoryblockheight as u32
+        }
+    };
+    wait_for_height(api, target, poll, timeout, || async move {
+        Ok(wallet_height(api).await?.api_result()?.height)
+    })
+    .await
+}
+
+async fn wait_for_height<F, Fut>(
+    api: &Factom,
+    target: u32,
+    poll: Duration,
+    timeout: Duration,
+    mut height: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<i64>>,
+{
+    utils::poll_until(api, poll, timeout, || {
+        let current = height();
+        async move {
+            let synced = current.await? >= i64::from(target);
+            Ok(if synced { Some(()) } else { None })
+        }
+    })
+    .await
+}
+
 /// Retrieve current properties of factom-walletd, including the wallet and wallet
 /// API versions.
 /// # Example
@@ -102,16 +198,98 @@ pub async fn wallet_height(api: &Factom) -> Result<ApiResponse<Height>> {
 /// async fn main() {
 ///   let client = Factom::open_node();
 ///   let response = walletd::wallet_properties(&client).await.unwrap();
-///   dbg!(&response);
-///   assert!(response.success());
+///   println!("walletd {}", response.result.walletversion);
+///   assert!(!response.result.walletapiversion.is_empty());
 /// }
 /// ```
-pub async fn wallet_properties(api: &Factom) -> Result<ApiResponse<Properties>> {
+pub async fn wallet_properties(api: &Factom) -> Result<ApiResponse<WalletProperties>> {
     let req = ApiRequest::new("properties");
     let response = walletd_call(api, req).await;
     parse(response).await
 }
 
+/// Signs arbitrary data with the private key of a factoid, entry credit or
+/// identity public key held in the wallet. The data is base64 encoded before
+/// being sent, the pubkey and base64 encoded signature are returned.
+///
+/// The data is sent in full, for large payloads it's recommended to sign a
+/// hash of the data instead, see sign_data_hashed.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let signer = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+///   let response = walletd::sign_data(&client, signer, b"Hello Factom").await.unwrap();
+///   assert!(!response.result.signature.is_empty());
+/// }
+/// ```
+pub async fn sign_data(api: &Factom, signer: &str, data: &[u8]) -> Result<ApiResponse<SignData>> {
+    let mut req = ApiRequest::new("sign-data");
+    req.params.insert("signer".to_string(), json!(signer));
+    req.params
+        .insert("data".to_string(), json!(base64::encode(data)));
+    let response = walletd_call(api, req).await;
+    parse(response).await
+}
+
+/// Hashes the data with sha256 and signs the digest with sign_data, following
+/// the recommended practice for large payloads. The digest that was signed is
+/// returned along with the pubkey and signature, all base64 encoded.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let signer = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+///   let document = vec![0u8; 1_000_000];
+///   let signed = walletd::sign_data_hashed(&client, signer, &document).await.unwrap();
+///   dbg!(&signed);
+/// }
+/// ```
+pub async fn sign_data_hashed(api: &Factom, signer: &str, data: &[u8]) -> Result<HashedSignature> {
+    let digest = Sha256::digest(data);
+    let signed = sign_data(api, signer, &digest).await?.api_result()?;
+    Ok(HashedSignature {
+        pubkey: signed.pubkey,
+        signature: signed.signature,
+        digest: base64::encode(digest),
+    })
+}
+
+/// Signs several payloads with the same signer using sign_data, a few at a time
+/// concurrently. The signatures are returned in the same order as the
+/// payloads, the first error encountered is returned if any signing fails.
+/// # Example
+/// ```no_run
+/// use factom::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let client = Factom::new();
+///   let signer = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+///   let payloads: [&[u8]; 2] = [b"first document", b"second document"];
+///   let signed = walletd::sign_data_batch(&client, signer, &payloads).await.unwrap();
+///   assert_eq!(signed.len(), 2);
+/// }
+/// ```
+pub async fn sign_data_batch(
+    api: &Factom,
+    signer: &str,
+    payloads: &[&[u8]],
+) -> Result<Vec<SignData>> {
+    let signed: Vec<Result<SignData>> = stream::iter(payloads)
+        .map(|data| async move { sign_data(api, signer, data).await?.api_result() })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await;
+    signed.into_iter().collect()
+}
+
 /// unlock-wallet function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UnlockWallet {
@@ -119,11 +297,18 @@ pub struct UnlockWallet {
     pub unlockeduntil: i64,
 }
 
+/// lock-wallet function
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockWallet {
+    pub success: bool,
+}
+
 /// wallet-backup function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WalletBackup {
     #[serde(rename = "wallet-seed")]
     pub wallet_seed: String,
+    #[serde(default, deserialize_with = "utils::null_as_default")]
     pub addresses: Vec<Address>,
 }
 
@@ -140,18 +325,52 @@ pub struct WalletBalances {
     pub ecaccountbalances: Ecaccountbalances,
 }
 
+impl WalletBalances {
+    /// Returns the factoid and entry credit amounts in flight, the acknowledged
+    /// balance minus the saved balance. Factoids are in factoshis.
+    pub fn pending(&self) -> (i64, i64) {
+        let fct = &self.fctaccountbalances;
+        let ec = &self.ecaccountbalances;
+        (fct.ack - fct.saved, ec.ack - ec.saved)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Fctaccountbalances {
     pub ack: i64,
     pub saved: i64,
 }
 
+impl Fctaccountbalances {
+    /// Returns the acknowledged balance as a u64, erroring if it is negative
+    pub fn ack_u64(&self) -> Result<u64> {
+        utils::non_negative(self.ack)
+    }
+
+    /// Returns the saved balance as a u64, erroring if it is negative
+    pub fn saved_u64(&self) -> Result<u64> {
+        utils::non_negative(self.saved)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ecaccountbalances {
     pub ack: i64,
     pub saved: i64,
 }
 
+impl Ecaccountbalances {
+    /// Returns the acknowledged balance as a u64, erroring if it is negative
+    pub fn ack_u64(&self) -> Result<u64> {
+        utils::non_negative(self.ack)
+    }
+
+    /// Returns the saved balance as a u64, erroring if it is negative
+    pub fn saved_u64(&self) -> Result<u64> {
+        utils::non_negative(self.saved)
+    }
+}
+
 /// sign-data function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SignData {
@@ -159,15 +378,184 @@ pub struct SignData {
     pub signature: String,
 }
 
+/// Result of sign_data_hashed, digest is the base64 encoded sha256 hash of the
+/// data which was signed
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HashedSignature {
+    pub pubkey: String,
+    pub signature: String,
+    pub digest: String,
+}
+
 /// wallet-properties function
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Properties {
+pub struct WalletProperties {
     pub walletversion: String,
     pub walletapiversion: String,
 }
 
-/// get-height function
+#[deprecated(note = "Renamed to WalletProperties to avoid confusion with factomd::Properties")]
+pub type Properties = WalletProperties;
+
+/// get-height function, the height of blocks cached by the wallet while syncing
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Height {
+pub struct WalletHeight {
     pub height: i64,
 }
+
+#[deprecated(note = "Renamed to WalletHeight to avoid confusion with factom::Height")]
+pub type Height = WalletHeight;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use crate::responses::ApiError;
+    use std::cell::Cell;
+
+    fn balances(fct: (i64, i64), ec: (i64, i64)) -> WalletBalances {
+        WalletBalances {
+            fctaccountbalances: Fctaccountbalances {
+                ack: fct.0,
+                saved: fct.1,
+            },
+            ecaccountbalances: Ecaccountbalances {
+                ack: ec.0,
+                saved: ec.1,
+            },
+        }
+    }
+
+    #[test]
+    fn empty_wallet_balances() {
+        let error = mock::error(-32603, "Internal error: Wallet is empty");
+        let server = mock::serve(vec![&error]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let response = fetch(wallet_balances(&client)).unwrap();
+        assert!(response.success());
+        assert_eq!(response.result, WalletBalances::default());
+        server.requests();
+        let failed = ApiResponse::<WalletBalances> {
+            error: ApiError {
+                code: -32603,
+                message: "Internal error: wallet locked".to_string(),
+            },
+            ..Default::default()
+        };
+        assert!(failed.or_empty_wallet().is_err());
+    }
+
+    #[test]
+    fn no_pending_balance() {
+        assert_eq!(balances((500, 500), (20, 20)).pending(), (0, 0));
+    }
+
+    #[test]
+    fn pending_balance() {
+        assert_eq!(balances((300, 500), (25, 20)).pending(), (-200, 5));
+    }
+
+    #[test]
+    fn wallet_properties_routes_to_walletd() {
+        let body = json!({"walletversion": "2.2.15", "walletapiversion": "v2"});
+        let server = mock::serve(vec![&mock::result(body)]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let response = fetch(wallet_properties(&client)).unwrap();
+        assert_eq!(response.result.walletversion, "2.2.15");
+        assert_eq!(response.result.walletapiversion, "v2");
+        assert_eq!(mock::method(&server.requests()[0]).0, "properties");
+    }
+
+    #[test]
+    fn unlock_then_lock() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let unlocked = mock::result(json!({"success": true, "unlockeduntil": now + 60}));
+        let locked = mock::result(json!({"success": true}));
+        let server = mock::serve(vec![&unlocked, &locked]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let response = fetch(unlock_wallet(&client, "test passphrase", 60)).unwrap();
+        assert!(response.result.success);
+        assert!(response.result.unlockeduntil > now);
+        assert!(fetch(lock_wallet(&client)).unwrap().result.success);
+        let requests = server.requests();
+        let (method, params) = mock::method(&requests[0]);
+        assert_eq!(method, "unlock-wallet");
+        assert_eq!(
+            params,
+            json!({"passphrase": "test passphrase", "timeout": 60})
+        );
+        assert_eq!(mock::method(&requests[1]).0, "lock-wallet");
+    }
+
+    #[test]
+    fn wallet_backup_parsed() {
+        let seed = ["yellow"; 12].join(" ");
+        let public = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
+        let secret = "Fs3E9gV6DXsYzf7Fqx1fVBQPQXV695eP3k5XbmHEZVRLkMdD9qCK";
+        let full = mock::result(json!({
+            "wallet-seed": seed,
+            "addresses": [{"public": public, "secret": secret}],
+            "identity-keys": []
+        }));
+        let empty = mock::result(json!({"wallet-seed": seed, "addresses": null}));
+        let server = mock::serve(vec![&full, &empty]);
+        let client = Factom::custom_node(&mock::closed_port(), &server.url);
+        let backup = fetch(wallet_backup(&client)).unwrap().result;
+        assert_eq!(backup.wallet_seed, seed);
+        assert_eq!(backup.addresses[0].secret, secret);
+        let backup = fetch(wallet_backup(&client)).unwrap().result;
+        assert!(backup.addresses.is_empty());
+        assert_eq!(mock::method(&server.requests()[0]).0, "wallet-backup");
+    }
+
+    #[test]
+    fn hashed_signature_digest() {
+        let data = b"a large document";
+        let expected = base64::encode(Sha256::digest(data));
+        let body = mock::result(json!({"pubkey": "pk", "signature": "sig"}));
+        let server = mock::serve(vec![&body]);
+        let client = Factom::custom_node(&server.url, &server.url);
+        let signed = fetch(sign_data_hashed(&client, "signer", data)).unwrap();
+        assert_eq!(signed.digest, expected);
+        assert_eq!(signed.signature, "sig");
+        let (method, params) = mock::method(&server.requests()[0]);
+        assert_eq!(method, "sign-data");
+        assert_eq!(params["data"], json!(expected));
+    }
+
+    #[test]
+    fn sign_batch_in_order() {
+        let server = mock::serve_with(3, |request| {
+            let (_, params) = mock::method(request);
+            let data = base64::decode(params["data"].as_str().unwrap()).unwrap();
+            let signature = format!("sig-{}", String::from_utf8(data).unwrap());
+            mock::result(json!({"pubkey": "pk", "signature": signature}))
+        });
+        let client = Factom::custom_node(&server.url, &server.url);
+        let payloads: [&[u8]; 3] = [b"one", b"two", b"three"];
+        let signed = fetch(sign_data_batch(&client, "signer", &payloads)).unwrap();
+        let signatures: Vec<&str> = signed.iter().map(|s| s.signature.as_str()).collect();
+        assert_eq!(signatures, vec!["sig-one", "sig-two", "sig-three"]);
+        server.requests();
+    }
+
+    #[test]
+    fn wallet_sync_reaches_target() {
+        let clock = Rc::new(ManualClock::new());
+        let client = Factom::new().with_clock(clock.clone());
+        let start = clock.now();
+        let height = Cell::new(98);
+        let poll = Duration::from_secs(5);
+        let query = wait_for_height(&client, 100, poll, Duration::from_secs(60), || {
+            height.set(height.get() + 1);
+            let current = height.get();
+            async move { Ok(current) }
+        });
+        fetch(query).unwrap();
+        assert_eq!(height.get(), 100);
+        assert_eq!(clock.now() - start, poll);
+    }
+}
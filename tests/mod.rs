@@ -1,4 +1,5 @@
 use ::factom::*;
+use futures::stream::StreamExt;
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use std::{iter, thread, time};
 
@@ -9,7 +10,7 @@ use std::{iter, thread, time};
 const FCT_PUB: &str = "FA2jK2HcLnRdS94dEcU27rF3meoJfpUcZPSinpb7AwQvPRY6RL1Q";
 const FCT_PRIV: &str = "Fs3E9gV6DXsYzf7Fqx1fVBQPQXV695eP3k5XbmHEZVRLkMdD9qCK";
 const EC_PUB: &str = "EC2MJzCcHqYJyujnPzjitEaHhtEPVBhmEWUKkv4SVaaKeYcq3fqK";
-// const EC_PRIV: &str = "Es3LS7zYa9DSzZuUC14HDpMinehmzz61JG1XFY62rX5pVDenH8Pk";
+const EC_PRIV: &str = "Es3LS7zYa9DSzZuUC14HDpMinehmzz61JG1XFY62rX5pVDenH8Pk";
 
 const ABLOCK_KEYMR: &str = "072334d94450296810cb647172812a5dc7ce518d29ecab411d47494d38ca4c88";
 const ABLOCK_HEIGHT: u32 = 220000;
@@ -76,6 +77,19 @@ fn all_addresses() {
     assert!(address_present.is_some());
 }
 
+#[test]
+fn generated_address_listed() {
+    let client = Factom::new();
+    let generated = fetch(generate::factoid_address(&client)).expect("Generating address");
+    let query = address::all_addresses(&client);
+    let response = fetch(query).expect("Fetching Query");
+    assert!(response
+        .result
+        .addresses
+        .iter()
+        .any(|address| address.public == generated.result.public));
+}
+
 #[test]
 fn rm_address() {
     let client = Factom::new();
@@ -87,6 +101,13 @@ fn rm_address() {
     let rm_response = fetch(rm_query).expect("Fetching Query");
     dbg!(&rm_response);
     assert!(rm_response.result.success);
+    let query = address::all_addresses(&client);
+    let response = fetch(query).expect("Fetching Query");
+    assert!(response
+        .result
+        .addresses
+        .iter()
+        .all(|remaining| remaining.public != address));
 }
 
 // Balance module
@@ -111,10 +132,12 @@ fn ec_balance() {
 #[test]
 fn multiple_balances() {
     let client = Factom::testnet_node();
-    let query = balance::multiple_fct_balances(&client, vec![FCT_PUB]);
+    let addresses = vec![FCT_PUB, KOINIFY_PUB, FCT_PUB];
+    let query = balance::multiple_fct_balances(&client, addresses);
     let response = fetch(query).expect("Fetching Query");
     dbg!(&response);
     assert_eq!(response.error.code, 0);
+    assert_eq!(response.result.balances.len(), 3);
 }
 
 #[test]
@@ -130,13 +153,19 @@ fn multiple_ec_balances() {
 #[test]
 fn ablock_height() {
     let client = Factom::open_node();
-    let query = block::ablock_by_height(&client, ABLOCK_HEIGHT);
+    let query = block::ablock_by_height(&client, ABLOCK_HEIGHT.into());
     let response = fetch(query).expect("Fetching Query");
     dbg!(&response);
     assert_eq!(
         response.result.ablock.header.dbheight,
         ABLOCK_HEIGHT as usize
     );
+    assert!(response
+        .result
+        .ablock
+        .abentries
+        .iter()
+        .all(|entry| entry.adminidtype.is_some()));
 }
 
 #[test]
@@ -154,15 +183,17 @@ fn admin_block() {
 #[test]
 fn anchors() {
     let client = Factom::open_node();
-    let query = block::anchors(&client, block::AnchorType::Height(ABLOCK_HEIGHT as usize));
+    let query = block::anchors(&client, block::AnchorType::Height(ABLOCK_HEIGHT.into()));
     let response = fetch(query).expect("Fetching Query");
     dbg!(&response);
+    assert_eq!(response.result.directoryblockheight, ABLOCK_HEIGHT as usize);
+    assert!(response.result.bitcoin.is_some());
 }
 
 #[test]
 fn dblock_height() {
     let client = Factom::open_node();
-    let query = block::dblock_by_height(&client, ABLOCK_HEIGHT);
+    let query = block::dblock_by_height(&client, ABLOCK_HEIGHT.into());
     let response = fetch(query).expect("Fetching Query");
     dbg!(&response.result.dblock.header);
     assert_eq!(
@@ -171,6 +202,15 @@ fn dblock_height() {
     );
 }
 
+#[test]
+fn canonical_chain() {
+    let client = Factom::open_node();
+    let reference = Factom::open_node();
+    let query = block::is_on_canonical_chain(&client, &reference, ABLOCK_HEIGHT.into());
+    let canonical = fetch(query).expect("Fetching Query");
+    assert!(canonical);
+}
+
 #[test]
 fn directory_block() {
     let client = Factom::open_node();
@@ -180,6 +220,21 @@ fn directory_block() {
     assert_eq!(response.result.header.timestamp, 1573694640);
 }
 
+#[test]
+fn entry_directory_block() {
+    let client = Factom::open_node();
+    let query = block::entry_directory_block(&client, ENTRY_HASH);
+    let response = fetch(query).expect("Fetching Query");
+    dbg!(&response.result.dblock.header);
+    let chain_present = response
+        .result
+        .dblock
+        .dbentries
+        .iter()
+        .any(|entry| entry.chainid == CHAIN_ID);
+    assert!(chain_present);
+}
+
 #[test]
 fn directory_block_head() {
     let client = Factom::open_node();
@@ -189,15 +244,59 @@ fn directory_block_head() {
     assert_eq!(response.error.code, 0);
 }
 
+#[test]
+fn directory_block_from_head() {
+    let client = Factom::open_node();
+    let query = block::directory_block_head(&client);
+    let head = fetch(query).expect("Fetching Query");
+    let query = block::directory_block(&client, &head.result.keymr);
+    let response = fetch(query).expect("Fetching Query");
+    dbg!(&response.result.header);
+    assert!(!response.result.entryblocklist.is_empty());
+}
+
 #[test]
 fn ecblock_height() {
     let client = Factom::open_node();
-    let query = block::ecblock_by_height(&client, 218668);
+    let query = block::ecblock_by_height(&client, Height(218668));
     let response = fetch(query).expect("Fetching Query");
     dbg!(&response.result.ecblock.header);
     assert_eq!(response.result.ecblock.header.bodyhash, ECBLOCK_BODYHASH);
 }
 
+#[test]
+fn entry_block() {
+    let client = Factom::open_node();
+    let head = fetch(chain::chain_head(&client, CHAIN_ID)).expect("Fetching Query");
+    let query = block::entry_block(&client, &head.result.chainhead);
+    let response = fetch(query).expect("Fetching Query");
+    assert_eq!(response.result.header.chainid, CHAIN_ID);
+    for entry in response.result.entrylist.iter() {
+        assert_eq!(entry.entryhash.len(), 64);
+        assert!(entry.timestamp >= response.result.header.timestamp);
+    }
+}
+
+#[test]
+fn entry_blocks_from_height() {
+    let client = Factom::open_node();
+    let heights = fetch(factomd::heights(&client)).expect("Fetching Query");
+    let start = heights.result.directoryblockheight as u32 - 6;
+    let query =
+        block::entry_blocks_from_height(&client, CHAIN_ID, start.into()).collect::<Vec<_>>();
+    let eblocks: Vec<_> = fetch(query)
+        .into_iter()
+        .map(|eblock| eblock.expect("Fetching Query"))
+        .collect();
+    dbg!(&eblocks);
+    assert!(eblocks
+        .iter()
+        .all(|eblock| eblock.header.dbheight as u32 >= start));
+    assert!(eblocks
+        .windows(2)
+        .all(|pair| pair[0].header.dbheight < pair[1].header.dbheight));
+}
+
 #[test]
 fn ec_block() {
     let client = Factom::open_node();
@@ -216,10 +315,37 @@ fn fct_block() {
     assert_eq!(response.result.fblock.bodymr, FBLOCK_BODYMR);
 }
 
+#[test]
+fn system_blocks_from_directory_block() {
+    let client = Factom::open_node();
+    let query = block::directory_block(&client, DBLOCK_KEYMR);
+    let dblock = fetch(query).expect("Fetching Query").result;
+    let keymr = |chainid: &str| {
+        dblock
+            .entryblocklist
+            .iter()
+            .find(|entry| entry.chainid == chainid)
+            .map(|entry| entry.keymr.clone())
+            .expect("System chain in directory block")
+    };
+    let height = dblock.header.sequencenumber;
+
+    let ecblock_keymr = keymr(SYSTEM_CHAINS[1]);
+    let query = block::entry_credit_block(&client, &ecblock_keymr);
+    let response = fetch(query).expect("Fetching Query");
+    assert_eq!(response.result.ecblock.header.dbheight, height);
+
+    let fblock_keymr = keymr(SYSTEM_CHAINS[2]);
+    let query = block::factoid_block(&client, &fblock_keymr);
+    let response = fetch(query).expect("Fetching Query");
+    assert_eq!(response.result.fblock.dbheight, height);
+    assert!(!response.result.fblock.transactions.is_empty());
+}
+
 #[test]
 fn fctblock_height() {
     let client = Factom::open_node();
-    let query = block::fblock_by_height(&client, FBLOCK_HEIGHT);
+    let query = block::fblock_by_height(&client, FBLOCK_HEIGHT.into());
     let response = fetch(query).expect("Fetching Query");
     dbg!(&response.result.fblock);
     assert_eq!(response.result.fblock.bodymr, FBLOCK_BODYMR);
@@ -234,6 +360,19 @@ fn chain_head() {
     assert!(response.success());
 }
 
+#[test]
+fn compose_identity_chain() {
+    let client = Factom::new();
+    let rand_name = &random_string(12);
+    let name = vec!["Api Client", "Test Identity", rand_name];
+    let query = compose::compose_id_chain(&client, name, vec![ID_PUB], EC_PUB, true);
+    let response = fetch(query).expect("Fetching Query");
+    dbg!(&response);
+    assert_eq!(response.result.commit.method, "commit-chain");
+    assert_eq!(response.result.reveal.method, "reveal-chain");
+    assert!(!response.result.reveal.params.entry.is_empty());
+}
+
 // This test only passes with a local or testnet factomd
 #[test]
 fn create_chain_and_make_entry() {
@@ -284,6 +423,36 @@ fn create_chain_and_make_entry() {
     assert!(reveal_response.success());
 }
 
+// This test only passes with a local or testnet factomd
+#[test]
+fn create_entries() {
+    let client = Factom::new();
+    let rand_ext_id = &random_string(12);
+    let ext_ids = vec!["Api Client", "Test Batch", rand_ext_id];
+    let content = random_string(32);
+    let query = utils::create_chain(&client, ext_ids, &content, EC_PUB);
+    let chain_response = fetch(query);
+    assert!(chain_response.success());
+    let chainid = chain_response.result.chainid;
+
+    // Wait for next block
+    thread::sleep(time::Duration::from_secs(10));
+
+    let entry = |chainid: &str| utils::EntrySpec {
+        chainid: chainid.to_string(),
+        extids: vec!["Api Client".to_string(), random_string(12)],
+        content: random_string(32),
+    };
+    let chainid = chainid.as_str();
+    let entries = vec![entry(chainid), entry("invalid chain"), entry(chainid)];
+    let query = utils::create_entries(&client, entries, EC_PUB);
+    let responses = fetch(query);
+    dbg!(&responses);
+    assert!(responses[0].as_ref().unwrap().success());
+    assert!(responses[1].is_err());
+    assert!(responses[2].as_ref().unwrap().success());
+}
+
 // Debug Module
 // Open_node doesn't expose debug functionality, these can all be tested with a
 //local factomd  node running a debug network with a command such as factomd -network=LOCAL
@@ -446,6 +615,16 @@ fn entry() {
     assert_eq!(response.result.chainid, CHAIN_ID);
 }
 
+#[test]
+fn entry_timestamp() {
+    let client = Factom::open_node();
+    let query = entry::entry_timestamp(&client, ENTRY_HASH, CHAIN_ID);
+    let timestamp = fetch(query).expect("Fetching Query");
+    dbg!(timestamp);
+    // Pegnet launched after 2019-06-01
+    assert!(timestamp > 1_559_347_200);
+}
+
 #[test]
 fn raw_data() {
     let client = Factom::open_node();
@@ -472,6 +651,18 @@ fn current_minute() {
     let response = fetch(query).expect("Fectching Query");
     dbg!(&response);
     assert!(response.success());
+    assert!((0..=10).contains(&response.result.minute));
+}
+
+#[test]
+fn is_stalled() {
+    let client = Factom::open_node();
+    let query = factomd::is_stalled(&client);
+    let stalled = fetch(query).expect("Fectching Query");
+    let mut stalls = factomd::watch_stalls(&client, time::Duration::from_secs(1)).boxed_local();
+    let first = fetch(stalls.next());
+    dbg!(stalled, &first);
+    assert!(first.expect("Stream item").is_ok());
 }
 
 #[test]
@@ -481,6 +672,10 @@ fn diagnostics() {
     let response = fetch(query).expect("Fectching Query");
     dbg!(&response);
     assert!(response.success());
+    assert!(!response.result.role.is_empty());
+    if !response.result.elections.inprogress {
+        assert_eq!(response.result.elections.fedid, None);
+    }
 }
 
 #[test]
@@ -499,6 +694,37 @@ fn heights() {
     let response = fetch(query).expect("Fectching Query");
     dbg!(&response);
     assert!(response.success());
+    assert!(response.result.leaderheight >= response.result.directoryblockheight);
+}
+
+// Requires a route to the network, 10.255.255.1 is unroutable so the connection
+// attempt hangs until the timeout
+#[test]
+fn connect_timeout() {
+    let host = "http://10.255.255.1:8088";
+    let client =
+        Factom::custom_node(host, host).with_connect_timeout(time::Duration::from_millis(200));
+    let query = factomd::heights(&client);
+    let err = fetch(query).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<FetchError>(),
+        Some(FetchError::ConnectTimeout)
+    ));
+}
+
+// As with connect_timeout the connection attempt hangs, so only the overall
+// timeout can end the call
+#[test]
+fn request_timeout() {
+    let host = "http://10.255.255.1:8088";
+    let client = Factom::custom_node(host, host).with_timeout(time::Duration::from_millis(200));
+    let started = time::Instant::now();
+    let err = fetch(factomd::heights(&client)).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<FetchError>(),
+        Some(FetchError::Timeout)
+    ));
+    assert!(started.elapsed() < time::Duration::from_secs(5));
 }
 
 #[test]
@@ -508,6 +734,7 @@ fn properties() {
     let response = fetch(query).expect("Fectching Query");
     dbg!(&response);
     assert!(response.success());
+    assert!(!response.result.factomdversion.is_empty());
 }
 
 #[test]
@@ -517,6 +744,10 @@ fn receipt() {
     let response = fetch(query).expect("Fectching Query");
     dbg!(&response);
     assert!(response.success());
+    let receipt = &response.result.receipt;
+    assert_eq!(receipt.entry.entryhash, ENTRY_HASH);
+    assert!(receipt.bitcoinblockhash.is_some());
+    assert!(response.result.verify().expect("Verifying receipt"));
 }
 
 // generate module
@@ -527,6 +758,10 @@ fn generate_factoid_address() {
     let response = fetch(query).expect("Fectching Query");
     dbg!(&response);
     assert!(response.success());
+    let public = response.result.public;
+    assert!(public.starts_with("FA"));
+    let address_type = address::validate_address(&public).expect("Valid address");
+    assert_eq!(address_type, address::AddressType::Factoid);
 }
 
 #[test]
@@ -536,6 +771,10 @@ fn generate_ec_address() {
     let response = fetch(query).expect("Fectching Query");
     dbg!(&response);
     assert!(response.success());
+    let public = response.result.public;
+    assert!(public.starts_with("EC"));
+    let address_type = address::validate_address(&public).expect("Valid address");
+    assert_eq!(address_type, address::AddressType::EntryCredit);
 }
 
 #[test]
@@ -548,6 +787,16 @@ fn generate_identity_key() {
 }
 
 // identity module
+#[test]
+fn new_identity_key() {
+    let client = Factom::new();
+    let query = identity::new_identity_key(&client);
+    let response = fetch(query).expect("Fectching Query");
+    assert!(response.result.public.starts_with("idpub"));
+    let query = identity::remove_id_key(&client, &response.result.public);
+    fetch(query).expect("Fectching Query");
+}
+
 #[test]
 fn all_id_keys() {
     let client = Factom::new();
@@ -596,10 +845,17 @@ fn id_key() {
 #[test]
 fn import_addresses() {
     let client = Factom::new();
-    let query = import::import_addresses(&client, vec![FCT_PRIV]);
+    let query = import::import_addresses(&client, vec![FCT_PRIV, EC_PRIV]);
     let response = fetch(query).expect("Fectching Query");
     dbg!(&response);
     assert!(response.success());
+    let publics: Vec<&str> = response
+        .result
+        .addresses
+        .iter()
+        .map(|address| address.public.as_str())
+        .collect();
+    assert_eq!(publics, vec![FCT_PUB, EC_PUB]);
     let query = address::remove_address(&client, FCT_PUB);
     fetch(query).expect("Fectching Query");
 }
@@ -611,6 +867,12 @@ fn import_id_keys() {
     let response = fetch(query).expect("Fectching Query");
     dbg!(&response);
     assert!(response.success());
+    let query = identity::all_id_keys(&client);
+    let keys = fetch(query).expect("Fectching Query").result.keys;
+    assert!(keys
+        .unwrap_or_default()
+        .iter()
+        .any(|key| key.public == ID_PUB));
     let query = identity::remove_id_key(&client, ID_PUB);
     fetch(query).expect("Fectching Query");
 }
@@ -671,14 +933,63 @@ fn new_and_delete_transaction() {
     assert!(response.success());
 }
 
+// This test only passes with a local or testnet factomd
+#[test]
+fn compose_and_submit_transaction() {
+    let client = Factom::new();
+    fetch(import::import_addresses(&client, vec![FCT_PRIV])).expect("Importing address");
+    let tx_name = &format!("api-client-{}", random_string(8));
+    let amount = 100_000;
+    fetch(tx::new_transaction(&client, tx_name)).expect("Fectching Query");
+    fetch(tx::add_input(&client, tx_name, FCT_PUB, amount)).expect("Fectching Query");
+    fetch(tx::add_output(&client, tx_name, KOINIFY_PUB, amount)).expect("Fectching Query");
+    fetch(tx::add_fee(&client, tx_name, FCT_PUB)).expect("Fectching Query");
+    fetch(tx::sign_transaction(&client, tx_name)).expect("Fectching Query");
+    let query = compose::compose_transaction(&client, tx_name);
+    let composed = fetch(query).expect("Fectching Query");
+    assert_eq!(composed.result.method, "factoid-submit");
+    let query = tx::factoid_submit(&client, &composed.result.params.transaction);
+    let response = fetch(query).expect("Fectching Query");
+    dbg!(&response);
+    assert!(!response.result.txid.is_empty());
+}
+
+#[test]
+fn new_transaction_collision() {
+    let client = Factom::new();
+    let tx_name = "api-client-collision-tx";
+    let query = tx::new_transaction(&client, tx_name);
+    fetch(query).expect("Fectching Query");
+    let query = tx::new_transaction(&client, tx_name);
+    let err = fetch(query).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<FetchError>(),
+        Some(FetchError::TransactionNameExists(_))
+    ));
+    let query = tx::delete_transaction(&client, tx_name);
+    fetch(query).expect("Fectching Query");
+}
+
 // Walletd module
+#[test]
+fn sign_data() {
+    let client = Factom::new();
+    fetch(import::import_addresses(&client, vec![FCT_PRIV])).expect("Importing address");
+    let query = walletd::sign_data(&client, FCT_PUB, b"Hello Factom");
+    let response = fetch(query).expect("Fetching Query");
+    assert!(response.success());
+    let signature = base64::decode(&response.result.signature).expect("Base64 signature");
+    assert_eq!(signature.len(), 64);
+    assert!(!response.result.pubkey.is_empty());
+}
+
 #[test]
 fn wallet_backup() {
     let client = Factom::new();
     let query = walletd::wallet_backup(&client);
     let response = fetch(query).expect("Fetching Query");
-    dbg!(&response);
     assert!(response.success());
+    assert!(!response.result.wallet_seed.is_empty());
 }
 
 #[test]
@@ -688,6 +999,7 @@ fn wallet_height() {
     let response = fetch(query).expect("Fetching Query");
     dbg!(&response);
     assert!(response.success());
+    assert!(response.result.height >= 0);
 }
 
 #[test]
@@ -697,4 +1009,6 @@ fn wallet_properties() {
     let response = fetch(query).expect("Fetching Query");
     dbg!(&response);
     assert!(response.success());
+    assert!(!response.result.walletversion.is_empty());
+    assert!(!response.result.walletapiversion.is_empty());
 }